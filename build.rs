@@ -0,0 +1,20 @@
+use std::{env, fs, path::PathBuf};
+
+// The chunked encoding lives next to the tree types so the CLI and this script
+// agree on it.
+#[allow(dead_code)]
+#[path = "src/command_tree.rs"]
+mod command_tree;
+
+fn main() {
+    println!("cargo:rerun-if-changed=schemas/command_tree.json");
+    println!("cargo:rerun-if-changed=src/command_tree.rs");
+
+    let raw = fs::read_to_string("schemas/command_tree.json").expect("read schemas/command_tree.json");
+    let tree: command_tree::CommandTree = serde_json::from_str(&raw).expect("invalid command_tree.json");
+    let (index, chunks) = command_tree::ChunkedTree::split(&tree).expect("encode command tree");
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR not set"));
+    fs::write(out_dir.join("tree_index.json"), index).expect("write tree_index.json");
+    fs::write(out_dir.join("tree_chunks.json"), chunks).expect("write tree_chunks.json");
+}
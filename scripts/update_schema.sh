@@ -6,5 +6,9 @@ openapi="$repo_root/schemas/openapi.yaml"
 tree="$repo_root/schemas/command_tree.json"
 
 "$repo_root/scripts/fetch_openapi.sh" "$openapi"
-cargo run --quiet --bin gen_command_tree -- --openapi "$openapi" --out "$tree"
+diff_args=()
+if [[ -f "$tree" ]]; then
+  diff_args=(--diff "$tree")
+fi
+cargo run --quiet --bin gen_command_tree -- --openapi "$openapi" --out "$tree" ${diff_args[@]+"${diff_args[@]}"} "$@"
 echo "wrote $tree"
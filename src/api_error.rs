@@ -0,0 +1,232 @@
+use reqwest::StatusCode;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+
+use crate::http::ResponseData;
+
+/// A request the API answered with a 4xx/5xx status, with the `errors` and
+/// `messages` of Cloudflare's response envelope.
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiError {
+    pub status: u16,
+    /// The `cf-ray` response header, which Cloudflare support asks for.
+    pub ray_id: Option<String>,
+    pub errors: Vec<ApiMessage>,
+    pub messages: Vec<ApiMessage>,
+    /// The response body when it is not a Cloudflare envelope (a proxy's HTML
+    /// error page, say), cut down to a readable length.
+    pub body: Option<String>,
+    /// Set when a later page of `--all` failed.
+    pub fetched: Option<Fetched>,
+}
+
+/// How far `--all` got before a page failed.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Fetched {
+    pub pages: usize,
+    pub items: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiMessage {
+    pub code: Option<i64>,
+    pub message: String,
+    pub documentation_url: Option<String>,
+    pub error_chain: Vec<ApiMessage>,
+}
+
+/// How much of a non-envelope body is kept.
+const MAX_BODY_CHARS: usize = 500;
+
+impl ApiError {
+    pub fn from_response(response: &ResponseData) -> Self {
+        let list = |key: &str| {
+            response
+                .body
+                .get(key)
+                .and_then(Value::as_array)
+                .map(|items| items.iter().map(ApiMessage::parse).collect::<Vec<_>>())
+        };
+        let (errors, messages) = (list("errors"), list("messages"));
+        let body = match (&errors, &response.body) {
+            (Some(_), _) | (None, Value::Null) => None,
+            (None, Value::String(text)) if text.trim().is_empty() => None,
+            (None, Value::String(text)) => Some(truncate(text.trim())),
+            (None, other) => Some(truncate(&other.to_string())),
+        };
+        Self {
+            status: response.status,
+            ray_id: response.ray_id.clone(),
+            errors: errors.unwrap_or_default(),
+            messages: messages.unwrap_or_default(),
+            body,
+            fetched: None,
+        }
+    }
+
+    /// The first Cloudflare error code, if the API gave one.
+    pub fn code(&self) -> Option<i64> {
+        self.errors.iter().find_map(|error| error.code)
+    }
+
+    /// The first error message, else the HTTP reason phrase.
+    pub fn message(&self) -> String {
+        match self.errors.first() {
+            Some(error) => error.message.clone(),
+            None => format!("http {}", self.status_line()),
+        }
+    }
+
+    fn status_line(&self) -> String {
+        match StatusCode::from_u16(self.status).ok().and_then(|status| status.canonical_reason()) {
+            Some(reason) => format!("{} {reason}", self.status),
+            None => self.status.to_string(),
+        }
+    }
+}
+
+impl ApiMessage {
+    /// Reads one `errors`/`messages` entry; codes may arrive as numbers or strings,
+    /// and bare strings are taken as the message.
+    fn parse(value: &Value) -> Self {
+        let text = |key: &str| value.get(key).and_then(Value::as_str).map(str::to_string);
+        Self {
+            code: value.get("code").and_then(|code| code.as_i64().or_else(|| code.as_str()?.parse().ok())),
+            message: value.as_str().map(str::to_string).or_else(|| text("message")).unwrap_or_default(),
+            documentation_url: text("documentation_url").filter(|url| !url.is_empty()),
+            error_chain: value
+                .get("error_chain")
+                .and_then(Value::as_array)
+                .map(|chain| chain.iter().map(Self::parse).collect())
+                .unwrap_or_default(),
+        }
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>, indent: &str, label: &str) -> fmt::Result {
+        write!(f, "\n{indent}{label}")?;
+        if let Some(code) = self.code {
+            write!(f, "[{code}] ")?;
+        }
+        write!(f, "{}", self.message)?;
+        for cause in &self.error_chain {
+            cause.write(f, &format!("{indent}  "), "caused by ")?;
+        }
+        if let Some(url) = &self.documentation_url {
+            write!(f, "\n{indent}  see {url}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "http {}", self.status_line())?;
+        if let Some(ray_id) = &self.ray_id {
+            write!(f, " (cf-ray {ray_id})")?;
+        }
+        for error in &self.errors {
+            error.write(f, "  ", "")?;
+        }
+        for message in &self.messages {
+            message.write(f, "  ", "note: ")?;
+        }
+        if let Some(body) = &self.body {
+            write!(f, "\n  {}", body.replace('\n', "\n  "))?;
+        }
+        if let Some(fetched) = &self.fetched {
+            write!(f, "\n  {fetched}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl fmt::Display for Fetched {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed after {} page(s) and {} item(s) were fetched", self.pages, self.items)
+    }
+}
+
+fn truncate(text: &str) -> String {
+    if text.chars().count() <= MAX_BODY_CHARS {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(MAX_BODY_CHARS).collect();
+    cut.push('…');
+    cut
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn response_error(status: u16, body: Value) -> ApiError {
+        ApiError::from_response(&ResponseData { status, body, ray_id: Some("8a1b2c3d4e5f-SJC".into()) })
+    }
+
+    #[test]
+    fn envelope_errors_keep_codes_chains_and_messages() {
+        let error = response_error(
+            400,
+            json!({
+                "success": false,
+                "errors": [{
+                    "code": "1004",
+                    "message": "DNS Validation Error",
+                    "documentation_url": "https://developers.cloudflare.com/dns/",
+                    "error_chain": [{"code": 9005, "message": "Content for A record must be a valid IPv4 address."}]
+                }],
+                "messages": ["retry with a valid address"],
+                "result": null
+            }),
+        );
+        assert_eq!(error.code(), Some(1004));
+        assert_eq!(error.message(), "DNS Validation Error");
+        assert_eq!(error.errors[0].error_chain[0].code, Some(9005));
+        assert_eq!(error.messages[0].message, "retry with a valid address");
+        assert!(error.body.is_none());
+        assert_eq!(
+            error.to_string(),
+            "http 400 Bad Request (cf-ray 8a1b2c3d4e5f-SJC)\n  \
+             [1004] DNS Validation Error\n    \
+             caused by [9005] Content for A record must be a valid IPv4 address.\n    \
+             see https://developers.cloudflare.com/dns/\n  \
+             note: retry with a valid address"
+        );
+    }
+
+    #[test]
+    fn non_envelope_bodies_are_kept_and_truncated() {
+        let error = response_error(502, json!("  <html>\nBad gateway</html>\n"));
+        assert_eq!(error.code(), None);
+        assert_eq!(error.message(), "http 502 Bad Gateway");
+        assert_eq!(error.body.as_deref(), Some("<html>\nBad gateway</html>"));
+        assert!(error.to_string().ends_with("\n  <html>\n  Bad gateway</html>"));
+
+        let long = "x".repeat(MAX_BODY_CHARS + 10);
+        let body = response_error(500, json!(long)).body.unwrap();
+        assert_eq!(body.chars().count(), MAX_BODY_CHARS + 1);
+        assert!(body.ends_with('…'));
+
+        assert_eq!(response_error(500, json!({"detail": "nope"})).body.as_deref(), Some(r#"{"detail":"nope"}"#));
+    }
+
+    #[test]
+    fn empty_bodies_leave_only_the_status() {
+        for body in [Value::Null, json!(""), json!(" \n")] {
+            let error = response_error(599, body);
+            assert!(error.body.is_none() && error.errors.is_empty());
+            assert_eq!(error.to_string(), "http 599 (cf-ray 8a1b2c3d4e5f-SJC)");
+        }
+    }
+
+    #[test]
+    fn fetched_progress_is_appended() {
+        let mut error = response_error(429, json!({"errors": [{"code": 10000, "message": "rate limited"}]}));
+        error.fetched = Some(Fetched { pages: 3, items: 150 });
+        assert!(error.to_string().ends_with("\n  failed after 3 page(s) and 150 item(s) were fetched"));
+    }
+}
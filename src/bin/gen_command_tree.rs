@@ -1,5 +1,8 @@
-use anyhow::{Context, Result};
-use clap::{Arg, Command};
+use anyhow::{Context, Result, anyhow};
+use clap::{Arg, ArgAction, Command};
+use cloudflare_cli::command_tree::CommandTree;
+use cloudflare_cli::diff::{TreeDiff, diff_trees};
+use cloudflare_cli::lint::LintReport;
 use std::fs;
 
 fn main() {
@@ -12,21 +15,147 @@ fn main() {
 fn run() -> Result<()> {
     let matches = Command::new("gen_command_tree")
         .arg(Arg::new("openapi").long("openapi").required(true))
-        .arg(Arg::new("out").long("out").required(true))
+        .arg(
+            Arg::new("out")
+                .long("out")
+                .required_unless_present_any(["diff", "lint"]),
+        )
+        .arg(
+            Arg::new("diff")
+                .long("diff")
+                .value_name("OLD_TREE")
+                .help("Report changes against an existing command_tree.json"),
+        )
+        .arg(
+            Arg::new("diff-json")
+                .long("diff-json")
+                .action(ArgAction::SetTrue)
+                .requires("diff")
+                .help("Emit the diff report as JSON"),
+        )
+        .arg(
+            Arg::new("fail-on-breaking")
+                .long("fail-on-breaking")
+                .action(ArgAction::SetTrue)
+                .requires("diff")
+                .help("Exit non-zero when the diff contains breaking changes"),
+        )
+        .arg(
+            Arg::new("lint")
+                .long("lint")
+                .action(ArgAction::SetTrue)
+                .help("Report spec inconsistencies and exit non-zero if there are any"),
+        )
+        .arg(
+            Arg::new("lint-json")
+                .long("lint-json")
+                .action(ArgAction::SetTrue)
+                .requires("lint")
+                .help("Emit the lint report as JSON"),
+        )
         .get_matches();
 
     let openapi_path = matches
         .get_one::<String>("openapi")
         .context("openapi path missing")?;
-    let out_path = matches
-        .get_one::<String>("out")
-        .context("out path missing")?;
 
     let raw = fs::read_to_string(openapi_path)
         .with_context(|| format!("read openapi {}", openapi_path))?;
-    let doc: serde_yaml::Value = serde_yaml::from_str(&raw).context("parse openapi yaml")?;
-    let tree = cloudflare_cli::openapi::build_command_tree(&doc)?;
-    let json = serde_json::to_string_pretty(&tree)?;
-    fs::write(out_path, json).with_context(|| format!("write {}", out_path))?;
+    let doc = cloudflare_cli::openapi::parse_spec(&raw)?;
+    let (tree, lint) = cloudflare_cli::openapi::lint_spec(&doc)?;
+
+    // Counted per operation: an untagged path can land in a resource a tag also uses.
+    let placed: Vec<usize> = tree
+        .resources
+        .iter()
+        .map(|res| res.ops.iter().filter(|op| op.path_derived).count())
+        .filter(|ops| *ops > 0)
+        .collect();
+    if !placed.is_empty() {
+        eprintln!(
+            "placed {} untagged operation(s) by path under {} resource(s)",
+            placed.iter().sum::<usize>(),
+            placed.len()
+        );
+    }
+
+    let diff = match matches.get_one::<String>("diff") {
+        Some(old_path) => {
+            let old_raw = fs::read_to_string(old_path).with_context(|| format!("read tree {}", old_path))?;
+            let old: CommandTree = serde_json::from_str(&old_raw).with_context(|| format!("parse tree {}", old_path))?;
+            let diff = diff_trees(&old, &tree);
+            print_diff(&diff, matches.get_flag("diff-json"))?;
+            Some(diff)
+        }
+        None => None,
+    };
+
+    if matches.get_flag("lint") {
+        print_lint(&lint, matches.get_flag("lint-json"))?;
+    }
+
+    if let Some(out_path) = matches.get_one::<String>("out") {
+        let json = serde_json::to_string_pretty(&tree)?;
+        fs::write(out_path, json).with_context(|| format!("write {}", out_path))?;
+    }
+
+    if let Some(diff) = diff
+        && matches.get_flag("fail-on-breaking")
+        && diff.breaking_count() > 0
+    {
+        return Err(anyhow!("{} breaking change(s)", diff.breaking_count()));
+    }
+    if matches.get_flag("lint") && !lint.issues.is_empty() {
+        return Err(anyhow!("{} lint issue(s)", lint.issues.len()));
+    }
+    Ok(())
+}
+
+fn print_lint(report: &LintReport, json: bool) -> Result<()> {
+    if json {
+        let out = serde_json::json!({
+            "issues": report.issues,
+            "total": report.issues.len(),
+            "counts": report.counts(),
+        });
+        println!("{}", serde_json::to_string_pretty(&out)?);
+        return Ok(());
+    }
+
+    for issue in &report.issues {
+        let target = match &issue.method {
+            Some(method) => format!("{method} {}", issue.path),
+            None => issue.path.clone(),
+        };
+        println!("{}: {target}: {}", issue.kind.as_str(), issue.detail);
+    }
+    println!("{} issue(s)", report.issues.len());
+    Ok(())
+}
+
+fn print_diff(diff: &TreeDiff, json: bool) -> Result<()> {
+    if json {
+        let out = serde_json::json!({
+            "changes": diff.changes,
+            "total": diff.changes.len(),
+            "breaking": diff.breaking_count(),
+        });
+        println!("{}", serde_json::to_string_pretty(&out)?);
+        return Ok(());
+    }
+
+    for change in &diff.changes {
+        let target = match &change.op {
+            Some(op) => format!("{} {}", change.resource, op),
+            None => change.resource.clone(),
+        };
+        let marker = if change.breaking { " (breaking)" } else { "" };
+        println!("{}: {target}: {}{marker}", change.kind.as_str(), change.detail);
+    }
+    println!(
+        "{} change(s), {} breaking",
+        diff.changes.len(),
+        diff.breaking_count()
+    );
     Ok(())
 }
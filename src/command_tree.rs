@@ -1,4 +1,7 @@
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[allow(dead_code)]
@@ -8,11 +11,39 @@ pub struct CommandTree {
     pub resources: Vec<Resource>,
 }
 
+impl CommandTree {
+    /// Looks an operation up the way the CLI resolves `<resource> <op>`: by name or
+    /// alias, falling back to operations cross-referenced from another resource.
+    pub fn find_op(&self, res_name: &str, op_name: &str) -> Option<&Operation> {
+        let matches_op = |op: &&Operation| op.name == op_name || op.aliases.iter().any(|alias| alias == op_name);
+        let resource = self
+            .resources
+            .iter()
+            .find(|res| res.name == res_name || res.aliases.iter().any(|alias| alias == res_name));
+        // Cross-referenced operations are listed under the resource, so its aliases reach them too.
+        let res_name = resource.map_or(res_name, |res| res.name.as_str());
+        resource
+            .and_then(|res| res.ops.iter().find(matches_op))
+            .or_else(|| {
+                self.resources
+                    .iter()
+                    .flat_map(|res| &res.ops)
+                    .find(|op| op.also_in.iter().any(|xref| xref.resource == res_name && xref.name == op_name))
+            })
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[allow(dead_code)]
 pub struct Resource {
     pub name: String,
     pub display_name: String,
+    /// Short names accepted in place of `name`, e.g. `dns`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// Named after the path because its operations carry no tags.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub path_derived: bool,
     pub ops: Vec<Operation>,
 }
 
@@ -20,6 +51,9 @@ pub struct Resource {
 #[allow(dead_code)]
 pub struct Operation {
     pub name: String,
+    /// Former long operationId-based names, still accepted on the command line.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
     pub display_name: String,
     pub method: String,
     pub path: String,
@@ -27,6 +61,102 @@ pub struct Operation {
     pub description: Option<String>,
     pub parameters: Vec<ParamDef>,
     pub has_body: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<RequestBody>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<ResponseSchema>,
+    /// API token permission groups the operation requires.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub permissions: Vec<String>,
+    /// Other resources whose tag also lists this operation; it is reachable there by
+    /// the name it had under that tag but only stored once.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub also_in: Vec<CrossRef>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub examples: Option<OperationExamples>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deprecated: bool,
+    /// How a list operation walks through its results, for `--all`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pagination: Option<Pagination>,
+    /// Untagged, so placed under a resource named after its path.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub path_derived: bool,
+}
+
+/// Another resource an operation is listed under, with the operationId-based name
+/// it had there when every tag held its own copy (`-get` suffixed on a clash).
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CrossRef {
+    pub resource: String,
+    pub name: String,
+}
+
+/// Pagination scheme of a list operation.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Pagination {
+    /// `page`/`per_page` query parameters, `result_info.total_pages` in the response.
+    Page,
+    /// `cursor` query parameter, next cursor in `result_info.cursor`.
+    Cursor,
+}
+
+impl Operation {
+    /// The recorded pagination scheme, else one inferred from the query parameters
+    /// for trees generated before it was recorded.
+    pub fn pagination_style(&self) -> Option<Pagination> {
+        self.pagination.or_else(|| {
+            let has_query = |name: &str| self.parameters.iter().any(|param| param.location == "query" && param.name == name);
+            if has_query("cursor") {
+                Some(Pagination::Cursor)
+            } else if has_query("page") {
+                Some(Pagination::Page)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[allow(dead_code)]
+pub struct RequestBody {
+    pub required: bool,
+    /// Media types accepted by the operation, e.g. `application/json`, `multipart/form-data`.
+    #[serde(default)]
+    pub content_types: Vec<String>,
+    /// Top-level properties of the JSON body, exposed as `location: "body"` flags.
+    pub fields: Vec<ParamDef>,
+    /// Fully resolved JSON schema of the `application/json` request body.
+    pub schema: Value,
+}
+
+/// Example values taken from the spec.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[allow(dead_code)]
+pub struct OperationExamples {
+    /// Parameter examples keyed by parameter name (not flag).
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub params: Map<String, Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+    /// Full example response body, envelope included.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<Value>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[allow(dead_code)]
+pub struct ResponseSchema {
+    /// Status code of the success response the shape was taken from.
+    pub status: String,
+    /// Whether `result` is an array rather than a single object.
+    pub list: bool,
+    /// Scalar fields of `result` (of each item for lists), in spec order.
+    pub columns: Vec<String>,
+    /// Structural schema of `result`: types, properties, items and enums only.
+    pub schema: Value,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -39,9 +169,206 @@ pub struct ParamDef {
     pub list: bool,
     pub schema_type: Option<String>,
     pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub enum_values: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minimum: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maximum: Option<f64>,
+    /// The value must be strictly greater than `minimum`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub exclusive_minimum: bool,
+    /// The value must be strictly less than `maximum`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub exclusive_maximum: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deprecated: bool,
+    /// OpenAPI serialization style (`form`, `spaceDelimited`, `pipeDelimited`,
+    /// `deepObject`); unset means the location's default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub style: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explode: Option<bool>,
+}
+
+impl ParamDef {
+    /// Object-valued query parameters, given as `KEY=VALUE` pairs or a JSON object.
+    pub fn is_query_object(&self) -> bool {
+        self.location == "query" && (self.schema_type.as_deref() == Some("object") || self.style.as_deref() == Some("deepObject"))
+    }
+
+    /// Lists of objects or arrays: each value is JSON (one item, or an array of
+    /// items) and is never split on commas.
+    pub fn has_json_items(&self) -> bool {
+        self.list && matches!(self.schema_type.as_deref(), Some("object" | "array"))
+    }
+}
+
+impl RequestBody {
+    pub fn accepts_json(&self) -> bool {
+        self.content_types.is_empty() || self.content_types.iter().any(|ct| ct.contains("json"))
+    }
+
+    pub fn accepts_multipart(&self) -> bool {
+        self.content_types.iter().any(|ct| ct == "multipart/form-data")
+    }
+
+    /// First media type that is sent as a raw payload rather than JSON or a form.
+    pub fn binary_content_type(&self) -> Option<&str> {
+        self.content_types
+            .iter()
+            .map(String::as_str)
+            .find(|ct| !ct.contains("json") && *ct != "multipart/form-data" && *ct != "application/x-www-form-urlencoded")
+    }
+}
+
+/// Where each resource sits in a chunked tree, so that one resource can be
+/// deserialized without touching the rest.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TreeIndex {
+    pub version: u32,
+    pub endpoint: String,
+    pub resources: Vec<IndexEntry>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct IndexEntry {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// Byte range of the resource's JSON in the chunk data; unset for names that
+    /// only exist as cross-references (`Operation::also_in`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span: Option<(usize, usize)>,
+    /// Entries whose resources hold operations cross-referenced under this name.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub xref_hosts: Vec<usize>,
 }
 
-pub fn load_command_tree() -> CommandTree {
-    let raw = include_str!("../schemas/command_tree.json");
-    serde_json::from_str(raw).expect("invalid command_tree.json")
+/// A command tree stored as a small index plus one compact JSON chunk per resource.
+pub struct ChunkedTree<'a> {
+    pub index: TreeIndex,
+    chunks: Cow<'a, str>,
+}
+
+impl<'a> ChunkedTree<'a> {
+    /// Encodes a tree as `(index, chunks)` JSON text.
+    pub fn split(tree: &CommandTree) -> serde_json::Result<(String, String)> {
+        let mut chunks = String::new();
+        let mut entries = Vec::new();
+        let mut positions: BTreeMap<&str, usize> = BTreeMap::new();
+        for res in &tree.resources {
+            let start = chunks.len();
+            chunks.push_str(&serde_json::to_string(res)?);
+            positions.insert(res.name.as_str(), entries.len());
+            entries.push(IndexEntry {
+                name: res.name.clone(),
+                aliases: res.aliases.clone(),
+                span: Some((start, chunks.len())),
+                xref_hosts: Vec::new(),
+            });
+        }
+        for (host, res) in tree.resources.iter().enumerate() {
+            for name in res.ops.iter().flat_map(|op| &op.also_in).map(|xref| &xref.resource) {
+                let idx = *positions.entry(name.as_str()).or_insert_with(|| {
+                    entries.push(IndexEntry {
+                        name: name.clone(),
+                        aliases: Vec::new(),
+                        span: None,
+                        xref_hosts: Vec::new(),
+                    });
+                    entries.len() - 1
+                });
+                if !entries[idx].xref_hosts.contains(&host) {
+                    entries[idx].xref_hosts.push(host);
+                }
+            }
+        }
+        let index = TreeIndex {
+            version: tree.version,
+            endpoint: tree.endpoint.clone(),
+            resources: entries,
+        };
+        Ok((serde_json::to_string(&index)?, chunks))
+    }
+
+    pub fn parse(index: &str, chunks: impl Into<Cow<'a, str>>) -> serde_json::Result<Self> {
+        Ok(Self {
+            index: serde_json::from_str(index)?,
+            chunks: chunks.into(),
+        })
+    }
+
+    pub fn load_all(&self) -> serde_json::Result<CommandTree> {
+        let resources = self
+            .index
+            .resources
+            .iter()
+            .filter_map(|entry| entry.span)
+            .map(|span| self.resource_at(span))
+            .collect::<serde_json::Result<_>>()?;
+        Ok(self.tree_with(resources))
+    }
+
+    /// The resource called (or aliased) `name` together with the resources holding
+    /// operations cross-referenced under it, which is all `find_op` and the CLI
+    /// need to resolve `<name> <op>`. `None` if no resource answers to `name`.
+    pub fn load_resource(&self, name: &str) -> serde_json::Result<Option<CommandTree>> {
+        let Some(entry) = self
+            .index
+            .resources
+            .iter()
+            .find(|entry| entry.name == name || entry.aliases.iter().any(|alias| alias == name))
+        else {
+            return Ok(None);
+        };
+        let mut spans: Vec<(usize, usize)> = entry.span.into_iter().collect();
+        for host in &entry.xref_hosts {
+            if let Some(span) = self.index.resources.get(*host).and_then(|host| host.span)
+                && !spans.contains(&span)
+            {
+                spans.push(span);
+            }
+        }
+        let resources = spans
+            .into_iter()
+            .map(|span| self.resource_at(span))
+            .collect::<serde_json::Result<_>>()?;
+        Ok(Some(self.tree_with(resources)))
+    }
+
+    /// Version and endpoint only, for commands that need no operations.
+    pub fn load_none(&self) -> CommandTree {
+        self.tree_with(Vec::new())
+    }
+
+    /// Whether every resource span lies within the chunk data, as it does unless
+    /// the index and chunks come from different trees or were cut short.
+    pub fn spans_valid(&self) -> bool {
+        self.index
+            .resources
+            .iter()
+            .filter_map(|entry| entry.span)
+            .all(|(start, end)| self.chunks.get(start..end).is_some())
+    }
+
+    fn resource_at(&self, (start, end): (usize, usize)) -> serde_json::Result<Resource> {
+        let chunk = self
+            .chunks
+            .get(start..end)
+            .ok_or_else(|| serde::de::Error::custom(format!("resource span {start}..{end} is outside the chunk data")))?;
+        serde_json::from_str(chunk)
+    }
+
+    fn tree_with(&self, resources: Vec<Resource>) -> CommandTree {
+        CommandTree {
+            version: self.index.version,
+            endpoint: self.index.endpoint.clone(),
+            resources,
+        }
+    }
 }
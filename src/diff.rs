@@ -0,0 +1,468 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::command_tree::{CommandTree, Operation, ParamDef, Resource};
+
+#[derive(Debug, Serialize, Clone)]
+pub struct TreeDiff {
+    pub changes: Vec<Change>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Change {
+    pub kind: ChangeKind,
+    pub breaking: bool,
+    pub resource: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub op: Option<String>,
+    pub detail: String,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    ResourceAdded,
+    ResourceRemoved,
+    OpAdded,
+    OpRemoved,
+    OpRenamed,
+    AliasRemoved,
+    NameRetargeted,
+    MethodChanged,
+    PathChanged,
+    ParamAdded,
+    ParamRemoved,
+    FlagRenamed,
+    ParamRequired,
+}
+
+impl ChangeKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ChangeKind::ResourceAdded => "resource_added",
+            ChangeKind::ResourceRemoved => "resource_removed",
+            ChangeKind::OpAdded => "op_added",
+            ChangeKind::OpRemoved => "op_removed",
+            ChangeKind::OpRenamed => "op_renamed",
+            ChangeKind::AliasRemoved => "alias_removed",
+            ChangeKind::NameRetargeted => "name_retargeted",
+            ChangeKind::MethodChanged => "method_changed",
+            ChangeKind::PathChanged => "path_changed",
+            ChangeKind::ParamAdded => "param_added",
+            ChangeKind::ParamRemoved => "param_removed",
+            ChangeKind::FlagRenamed => "flag_renamed",
+            ChangeKind::ParamRequired => "param_required",
+        }
+    }
+}
+
+impl TreeDiff {
+    pub fn breaking_count(&self) -> usize {
+        self.changes.iter().filter(|change| change.breaking).count()
+    }
+}
+
+impl Change {
+    fn new(kind: ChangeKind, breaking: bool, resource: &str, op: Option<&str>, detail: String) -> Self {
+        Self {
+            kind,
+            breaking,
+            resource: resource.to_string(),
+            op: op.map(str::to_string),
+            detail,
+        }
+    }
+}
+
+/// Compares two command trees from the CLI user's point of view: an old invocation
+/// (by any name or alias) that no longer resolves or now runs another operation, a
+/// renamed flag or a newly required parameter is breaking. Operations are matched
+/// across versions by operationId (`display_name`).
+pub fn diff_trees(old: &CommandTree, new: &CommandTree) -> TreeDiff {
+    let mut changes = Vec::new();
+
+    let old_resources: BTreeSet<&str> = old.resources.iter().map(|res| res.name.as_str()).collect();
+    let new_resources: BTreeSet<&str> = new.resources.iter().map(|res| res.name.as_str()).collect();
+    for name in new_resources.difference(&old_resources) {
+        changes.push(Change::new(ChangeKind::ResourceAdded, false, name, None, "resource added".to_string()));
+    }
+    for name in old_resources.difference(&new_resources) {
+        // Still breaking-free if every old command resolves through an alias or cross-reference.
+        let res = old.resources.iter().find(|res| res.name == *name);
+        let reachable = res.is_some_and(|res| res.ops.iter().all(|op| new.find_op(name, &op.name).is_some()));
+        changes.push(Change::new(ChangeKind::ResourceRemoved, !reachable, name, None, "resource removed".to_string()));
+    }
+
+    let old_ops = ops_by_id(old);
+    let new_ops = ops_by_id(new);
+
+    for res in &old.resources {
+        for op in &res.ops {
+            if new.find_op(&res.name, &op.name).is_none() {
+                match new_ops.get(op.display_name.as_str()) {
+                    Some((new_res, new_op)) => changes.push(Change::new(
+                        ChangeKind::OpRenamed,
+                        true,
+                        &res.name,
+                        Some(&op.name),
+                        format!("now `{new_res} {}`", new_op.name),
+                    )),
+                    None => changes.push(Change::new(
+                        ChangeKind::OpRemoved,
+                        true,
+                        &res.name,
+                        Some(&op.name),
+                        format!("{} {} removed", op.method, op.path),
+                    )),
+                }
+                continue;
+            }
+            let mut lost = Vec::new();
+            for (res_name, op_name) in invocations(old, res, op) {
+                match new.find_op(res_name, op_name) {
+                    None => lost.push(format!("`{res_name} {op_name}`")),
+                    Some(now) if !same_operation(op, now) => changes.push(Change::new(
+                        ChangeKind::NameRetargeted,
+                        true,
+                        &res.name,
+                        Some(&op.name),
+                        format!("`{res_name} {op_name}` now runs {} ({} {})", now.display_name, now.method, now.path),
+                    )),
+                    Some(_) => {}
+                }
+            }
+            if !lost.is_empty() {
+                changes.push(Change::new(
+                    ChangeKind::AliasRemoved,
+                    true,
+                    &res.name,
+                    Some(&op.name),
+                    format!("{} no longer {}", lost.join(", "), if lost.len() == 1 { "resolves" } else { "resolve" }),
+                ));
+            }
+        }
+    }
+
+    for (op_id, (res_name, op)) in &new_ops {
+        let Some((_, old_op)) = old_ops.get(op_id) else {
+            changes.push(Change::new(
+                ChangeKind::OpAdded,
+                false,
+                res_name,
+                Some(&op.name),
+                format!("{} {}", op.method, op.path),
+            ));
+            continue;
+        };
+        diff_op(res_name, old_op, op, &mut changes);
+    }
+
+    TreeDiff { changes }
+}
+
+/// Every `resource op` pair that runs `op` in `tree`: its resource's name and aliases
+/// with its own name and aliases, and the names it has under other resources.
+fn invocations<'a>(tree: &'a CommandTree, res: &'a Resource, op: &'a Operation) -> Vec<(&'a str, &'a str)> {
+    let op_names: Vec<&str> = std::iter::once(&op.name).chain(&op.aliases).map(String::as_str).collect();
+    let mut out: Vec<(&str, &str)> = std::iter::once(&res.name)
+        .chain(&res.aliases)
+        .flat_map(|res_name| op_names.iter().map(move |op_name| (res_name.as_str(), *op_name)))
+        .collect();
+    for xref in &op.also_in {
+        let holder = tree.resources.iter().find(|other| other.name == xref.resource);
+        let res_names = std::iter::once(&xref.resource).chain(holder.into_iter().flat_map(|other| &other.aliases));
+        out.extend(res_names.map(|res_name| (res_name.as_str(), xref.name.as_str())));
+    }
+    // A name taken by something else in the old tree was never this operation's.
+    out.retain(|(res_name, op_name)| tree.find_op(res_name, op_name).is_some_and(|found| std::ptr::eq(found, op)));
+    out
+}
+
+/// Whether a name still runs the same thing: the same operationId, or the same endpoint.
+fn same_operation(old: &Operation, new: &Operation) -> bool {
+    old.display_name == new.display_name || (old.method == new.method && old.path == new.path)
+}
+
+/// First occurrence of every operationId; older trees listed multi-tag operations
+/// under each tag.
+fn ops_by_id(tree: &CommandTree) -> BTreeMap<&str, (&str, &Operation)> {
+    let mut out = BTreeMap::new();
+    for res in &tree.resources {
+        for op in &res.ops {
+            out.entry(op.display_name.as_str()).or_insert((res.name.as_str(), op));
+        }
+    }
+    out
+}
+
+fn diff_op(res_name: &str, old: &Operation, new: &Operation, changes: &mut Vec<Change>) {
+    let op_name = Some(new.name.as_str());
+    if old.method != new.method {
+        changes.push(Change::new(
+            ChangeKind::MethodChanged,
+            true,
+            res_name,
+            op_name,
+            format!("method {} -> {}", old.method, new.method),
+        ));
+    }
+    if old.path != new.path {
+        changes.push(Change::new(
+            ChangeKind::PathChanged,
+            false,
+            res_name,
+            op_name,
+            format!("path {} -> {}", old.path, new.path),
+        ));
+    }
+
+    // Trees generated before body schemas were captured know nothing about body fields.
+    let with_body = old.body.is_some() && new.body.is_some();
+    let old_params = params_by_key(old, with_body);
+    let new_params = params_by_key(new, with_body);
+    for (key, param) in &new_params {
+        match old_params.get(key) {
+            None => changes.push(Change::new(
+                ChangeKind::ParamAdded,
+                param.required,
+                res_name,
+                op_name,
+                format!("--{} added ({}, required: {})", param.flag, param.location, param.required),
+            )),
+            Some(old_param) => {
+                if old_param.flag != param.flag {
+                    changes.push(Change::new(
+                        ChangeKind::FlagRenamed,
+                        true,
+                        res_name,
+                        op_name,
+                        format!("--{} renamed to --{}", old_param.flag, param.flag),
+                    ));
+                }
+                if param.required && !old_param.required {
+                    changes.push(Change::new(
+                        ChangeKind::ParamRequired,
+                        true,
+                        res_name,
+                        op_name,
+                        format!("--{} is now required", param.flag),
+                    ));
+                }
+            }
+        }
+    }
+    for (key, param) in &old_params {
+        if !new_params.contains_key(key) {
+            changes.push(Change::new(
+                ChangeKind::ParamRemoved,
+                true,
+                res_name,
+                op_name,
+                format!("--{} removed ({})", param.flag, param.location),
+            ));
+        }
+    }
+}
+
+fn params_by_key(op: &Operation, with_body: bool) -> BTreeMap<(&str, &str), &ParamDef> {
+    let body_fields = op.body.iter().filter(|_| with_body).flat_map(|body| &body.fields);
+    op.parameters
+        .iter()
+        .chain(body_fields)
+        .map(|param| ((param.location.as_str(), param.name.as_str()), param))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_tree::CrossRef;
+    use serde_json::{Value, json};
+
+    fn param(name: &str, location: &str, required: bool) -> Value {
+        json!({
+            "name": name,
+            "flag": name.replace('_', "-"),
+            "location": location,
+            "required": required,
+            "list": false,
+            "schema_type": "string",
+            "description": null,
+        })
+    }
+
+    fn op(name: &str, op_id: &str, method: &str, path: &str, parameters: Vec<Value>) -> Value {
+        json!({
+            "name": name,
+            "display_name": op_id,
+            "method": method,
+            "path": path,
+            "summary": null,
+            "description": null,
+            "parameters": parameters,
+            "has_body": false,
+        })
+    }
+
+    fn tree(resources: Vec<(&str, Vec<Value>)>) -> CommandTree {
+        let resources: Vec<Value> = resources
+            .into_iter()
+            .map(|(name, ops)| json!({"name": name, "display_name": name, "ops": ops}))
+            .collect();
+        serde_json::from_value(json!({"version": 4, "endpoint": "https://api.example.com", "resources": resources}))
+            .expect("valid tree")
+    }
+
+    fn kinds(diff: &TreeDiff) -> Vec<(ChangeKind, bool)> {
+        diff.changes.iter().map(|change| (change.kind, change.breaking)).collect()
+    }
+
+    #[test]
+    fn identical_trees_have_no_changes() {
+        let ops = vec![op("list", "dns-list", "GET", "/dns", vec![param("page", "query", false)])];
+        let old = tree(vec![("dns", ops)]);
+        let diff = diff_trees(&old, &old.clone());
+        assert!(diff.changes.is_empty());
+    }
+
+    #[test]
+    fn renamed_and_removed_operations_are_breaking() {
+        let old = tree(vec![(
+            "dns",
+            vec![
+                op("dns-list", "dns-list", "GET", "/dns", vec![]),
+                op("dns-purge", "dns-purge", "POST", "/dns/purge", vec![]),
+            ],
+        )]);
+        let new = tree(vec![("records", vec![op("list", "dns-list", "GET", "/dns", vec![])])]);
+        let diff = diff_trees(&old, &new);
+        let renamed = diff.changes.iter().find(|change| change.kind == ChangeKind::OpRenamed).unwrap();
+        assert_eq!(renamed.detail, "now `records list`");
+        assert!(kinds(&diff).contains(&(ChangeKind::OpRemoved, true)));
+        assert!(kinds(&diff).contains(&(ChangeKind::ResourceRemoved, true)));
+        assert!(kinds(&diff).contains(&(ChangeKind::ResourceAdded, false)));
+    }
+
+    #[test]
+    fn operation_kept_as_alias_is_not_a_change() {
+        let old = tree(vec![("dns", vec![op("dns-list", "dns-list", "GET", "/dns", vec![])])]);
+        let mut new = tree(vec![("dns", vec![op("list", "dns-list", "GET", "/dns", vec![])])]);
+        new.resources[0].ops[0].aliases.push("dns-list".to_string());
+        assert!(diff_trees(&old, &new).changes.is_empty());
+    }
+
+    #[test]
+    fn removed_resource_reachable_through_cross_reference_is_not_breaking() {
+        let old = tree(vec![
+            ("alpha", vec![op("shared", "shared", "GET", "/a", vec![])]),
+            ("beta", vec![op("shared", "shared", "GET", "/a", vec![])]),
+        ]);
+        let mut new = tree(vec![("alpha", vec![op("shared", "shared", "GET", "/a", vec![])])]);
+        new.resources[0].ops[0].also_in.push(CrossRef {
+            resource: "beta".to_string(),
+            name: "shared".to_string(),
+        });
+        assert_eq!(kinds(&diff_trees(&old, &new)), vec![(ChangeKind::ResourceRemoved, false)]);
+    }
+
+    #[test]
+    fn parameter_changes_are_breaking_unless_optional_additions() {
+        let old = tree(vec![(
+            "dns",
+            vec![op(
+                "list",
+                "dns-list",
+                "GET",
+                "/dns",
+                vec![param("zone_id", "path", true), param("page", "query", false), param("name", "query", false)],
+            )],
+        )]);
+        let mut renamed = param("zone_id", "path", true);
+        renamed["flag"] = json!("zone");
+        let new = tree(vec![(
+            "dns",
+            vec![op(
+                "list",
+                "dns-list",
+                "GET",
+                "/dns",
+                vec![
+                    renamed,
+                    param("page", "query", true),
+                    param("order", "query", false),
+                    param("type", "query", true),
+                ],
+            )],
+        )]);
+        let diff = diff_trees(&old, &new);
+        let mut found = kinds(&diff);
+        found.sort_by_key(|(kind, _)| kind.as_str());
+        assert_eq!(
+            found,
+            vec![
+                (ChangeKind::FlagRenamed, true),
+                (ChangeKind::ParamAdded, false),
+                (ChangeKind::ParamAdded, true),
+                (ChangeKind::ParamRemoved, true),
+                (ChangeKind::ParamRequired, true),
+            ]
+        );
+        assert_eq!(diff.breaking_count(), 4);
+    }
+
+    #[test]
+    fn method_change_is_breaking_but_path_change_is_not() {
+        let old = tree(vec![("dns", vec![op("update", "dns-update", "PUT", "/dns/{id}", vec![])])]);
+        let new = tree(vec![("dns", vec![op("update", "dns-update", "PATCH", "/v2/dns/{id}", vec![])])]);
+        assert_eq!(
+            kinds(&diff_trees(&old, &new)),
+            vec![(ChangeKind::MethodChanged, true), (ChangeKind::PathChanged, false)]
+        );
+    }
+
+    #[test]
+    fn dropped_aliases_and_cross_references_are_breaking() {
+        let mut old = tree(vec![
+            ("dns-records", vec![op("create", "dns-create", "POST", "/dns", vec![])]),
+            ("zones", vec![op("list", "zones-list", "GET", "/zones", vec![])]),
+        ]);
+        old.resources[0].aliases.push("dns".to_string());
+        old.resources[0].ops[0].aliases.push("dns-create".to_string());
+        old.resources[0].ops[0].also_in.push(CrossRef {
+            resource: "records".to_string(),
+            name: "create-record".to_string(),
+        });
+        let new = tree(vec![
+            ("dns-records", vec![op("create", "dns-create", "POST", "/dns", vec![])]),
+            ("zones", vec![op("list", "zones-list", "GET", "/zones", vec![])]),
+        ]);
+        let diff = diff_trees(&old, &new);
+        assert_eq!(kinds(&diff), vec![(ChangeKind::AliasRemoved, true)]);
+        assert_eq!(
+            diff.changes[0].detail,
+            "`dns-records dns-create`, `dns create`, `dns dns-create`, `records create-record` no longer resolve"
+        );
+    }
+
+    #[test]
+    fn names_that_now_run_another_operation_are_breaking() {
+        let mut old = tree(vec![
+            ("dns-records", vec![op("get", "dns-get", "GET", "/dns/{id}", vec![])]),
+            ("dns-settings", vec![op("get", "dns-settings-get", "GET", "/dns/settings", vec![])]),
+        ]);
+        old.resources[0].aliases.push("dns".to_string());
+        let mut new = old.clone();
+        new.resources[0].aliases.clear();
+        new.resources[1].aliases.push("dns".to_string());
+        let diff = diff_trees(&old, &new);
+        assert_eq!(kinds(&diff), vec![(ChangeKind::NameRetargeted, true)]);
+        assert_eq!(diff.changes[0].detail, "`dns get` now runs dns-settings-get (GET /dns/settings)");
+
+        let mut moved = old.clone();
+        moved.resources[0].ops[0].display_name = "dns-settings-get".to_string();
+        moved.resources[0].ops[0].path = "/dns/settings".to_string();
+        moved.resources[1].ops.clear();
+        let found = kinds(&diff_trees(&old, &moved));
+        assert!(found.contains(&(ChangeKind::NameRetargeted, true)));
+    }
+}
@@ -0,0 +1,16 @@
+use anyhow::{Context, Result};
+
+use crate::command_tree::{ChunkedTree, CommandTree};
+
+// Written by build.rs from schemas/command_tree.json.
+const INDEX: &str = include_str!(concat!(env!("OUT_DIR"), "/tree_index.json"));
+const CHUNKS: &str = include_str!(concat!(env!("OUT_DIR"), "/tree_chunks.json"));
+
+/// The command tree compiled into the binary, in chunked form.
+pub fn embedded_tree() -> Result<ChunkedTree<'static>> {
+    ChunkedTree::parse(INDEX, CHUNKS).context("invalid embedded command tree index")
+}
+
+pub fn load_command_tree() -> Result<CommandTree> {
+    embedded_tree()?.load_all().context("invalid embedded command tree")
+}
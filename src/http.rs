@@ -1,7 +1,9 @@
 use anyhow::{Context, Result};
+use reqwest::blocking::multipart::{Form, Part};
 use reqwest::blocking::Client;
 use reqwest::Method;
 use serde_json::Value;
+use std::path::PathBuf;
 
 pub struct HttpClient {
     base_url: String,
@@ -9,9 +11,32 @@ pub struct HttpClient {
     client: Client,
 }
 
+#[derive(Clone)]
+pub enum Body {
+    Json(Value),
+    Multipart(Vec<FormPart>),
+    Binary { content_type: String, data: Vec<u8> },
+}
+
+#[derive(Clone)]
+pub struct FormPart {
+    pub name: String,
+    pub value: FormValue,
+    /// Overrides the part's content type (guessed from the file name otherwise).
+    pub content_type: Option<String>,
+}
+
+#[derive(Clone)]
+pub enum FormValue {
+    Text(String),
+    File(PathBuf),
+}
+
 pub struct ResponseData {
     pub status: u16,
     pub body: Value,
+    /// The `cf-ray` header, when the response came through Cloudflare's edge.
+    pub ray_id: Option<String>,
 }
 
 impl HttpClient {
@@ -33,7 +58,7 @@ impl HttpClient {
         path: &str,
         query: &[(String, String)],
         headers: &[(String, String)],
-        body: Option<Value>,
+        body: Option<Body>,
     ) -> Result<ResponseData> {
         let mut url = build_url(&self.base_url, path)?;
         {
@@ -48,8 +73,17 @@ impl HttpClient {
             .request(method, url)
             .header("authorization", format!("Bearer {}", self.api_token));
 
-        if let Some(value) = body {
-            req = req.header("content-type", "application/json").json(&value);
+        match body {
+            Some(Body::Json(value)) => {
+                req = req.header("content-type", "application/json").json(&value);
+            }
+            Some(Body::Multipart(parts)) => {
+                req = req.multipart(build_form(parts)?);
+            }
+            Some(Body::Binary { content_type, data }) => {
+                req = req.header("content-type", content_type).body(data);
+            }
+            None => {}
         }
 
         for (k, v) in headers {
@@ -58,16 +92,41 @@ impl HttpClient {
 
         let resp = req.send().context("send request")?;
         let status = resp.status();
+        let ray_id = resp
+            .headers()
+            .get("cf-ray")
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
         let text = resp.text().context("read response body")?;
-        let body = serde_json::from_str(&text).unwrap_or_else(|_| Value::String(text));
+        let body = serde_json::from_str(&text).unwrap_or(Value::String(text));
 
         Ok(ResponseData {
             status: status.as_u16(),
             body,
+            ray_id,
         })
     }
 }
 
+fn build_form(parts: Vec<FormPart>) -> Result<Form> {
+    let mut form = Form::new();
+    for part in parts {
+        let mut body = match part.value {
+            FormValue::Text(text) => Part::text(text),
+            FormValue::File(path) => {
+                Part::file(&path).with_context(|| format!("read form file {}", path.display()))?
+            }
+        };
+        if let Some(content_type) = &part.content_type {
+            body = body
+                .mime_str(content_type)
+                .with_context(|| format!("invalid content type {content_type}"))?;
+        }
+        form = form.part(part.name, body);
+    }
+    Ok(form)
+}
+
 fn build_url(base: &str, path: &str) -> Result<reqwest::Url> {
     let base = base.trim_end_matches('/');
     let path = path.trim_start_matches('/');
@@ -1,3 +1,11 @@
+pub mod api_error;
 pub mod command_tree;
+pub mod diff;
+pub mod embedded;
 pub mod http;
+pub mod lint;
 pub mod openapi;
+pub mod output;
+pub mod query;
+pub mod tree_cache;
+pub mod validate;
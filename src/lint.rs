@@ -0,0 +1,76 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Spec problems the generator works around silently; see `openapi::lint_spec`.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct LintReport {
+    pub issues: Vec<LintIssue>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct LintIssue {
+    pub kind: LintKind,
+    pub path: String,
+    /// Unset for problems in path-level parameters.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operation_id: Option<String>,
+    pub detail: String,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum LintKind {
+    /// `{placeholder}` in the path template without a matching path parameter.
+    MissingPathParam,
+    /// Path parameter that does not appear in the path template.
+    UnusedPathParam,
+    /// Two parameters or body fields that normalize to the same flag.
+    DuplicateFlag,
+    /// Parameter or body field named like a flag the CLI defines, given a prefix.
+    ReservedFlag,
+    /// Operation name that clashed with another and was given a suffix.
+    OpNameCollision,
+    /// Path-derived resource name that clashed with a tag or built-in command and was given a suffix.
+    ResourceNameCollision,
+    /// Parameter dropped for lacking `name` or `in`, or an unresolvable `$ref`.
+    SkippedParam,
+    /// Name an operation had under a secondary tag that no longer reaches it.
+    LostLegacyName,
+}
+
+impl LintKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LintKind::MissingPathParam => "missing_path_param",
+            LintKind::UnusedPathParam => "unused_path_param",
+            LintKind::DuplicateFlag => "duplicate_flag",
+            LintKind::ReservedFlag => "reserved_flag",
+            LintKind::OpNameCollision => "op_name_collision",
+            LintKind::ResourceNameCollision => "resource_name_collision",
+            LintKind::SkippedParam => "skipped_param",
+            LintKind::LostLegacyName => "lost_legacy_name",
+        }
+    }
+}
+
+impl LintReport {
+    pub fn push(&mut self, kind: LintKind, path: &str, method: Option<&str>, operation_id: Option<&str>, detail: String) {
+        self.issues.push(LintIssue {
+            kind,
+            path: path.to_string(),
+            method: method.map(str::to_uppercase),
+            operation_id: operation_id.map(str::to_string),
+            detail,
+        });
+    }
+
+    pub fn counts(&self) -> BTreeMap<&'static str, usize> {
+        let mut counts = BTreeMap::new();
+        for issue in &self.issues {
+            *counts.entry(issue.kind.as_str()).or_insert(0) += 1;
+        }
+        counts
+    }
+}
@@ -1,21 +1,171 @@
 use anyhow::{Context, Result, anyhow};
+use clap::builder::{PossibleValue, TypedValueParser};
+use clap::error::ErrorKind;
+use clap::parser::ValueSource;
 use clap::{Arg, ArgAction, Command};
-use cloudflare_cli::command_tree::{CommandTree, Operation, ParamDef};
-use cloudflare_cli::http::HttpClient;
+use cloudflare_cli::api_error::{ApiError, Fetched};
+use cloudflare_cli::command_tree::{CommandTree, Operation, Pagination, ParamDef, Resource};
+use cloudflare_cli::http::{Body, FormPart, FormValue, HttpClient, ResponseData};
+use cloudflare_cli::output::{OutputFormat, render, select_fields};
+use cloudflare_cli::query::Query;
+use cloudflare_cli::validate::validate;
+use reqwest::Method;
 use serde_json::{Value, json};
-use std::{env, fs, io::Write};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+    env,
+    ffi::OsString,
+    fmt, fs,
+    io::Write,
+    sync::OnceLock,
+};
 
 fn main() {
     if let Err(err) = run() {
-        eprintln!("error: {err}");
-        std::process::exit(1);
+        let kind = ExitKind::of(&err);
+        let args: Vec<OsString> = env::args_os().collect();
+        if global_value(&args, "error-format").as_deref() == Some("json") {
+            eprintln!("{}", error_envelope(&err, kind));
+        } else {
+            eprintln!("error: {err:#}");
+        }
+        std::process::exit(kind.code());
     }
 }
 
+/// Why the CLI failed, as told to scripts by the exit code (see the README).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExitKind {
+    /// Anything not covered below.
+    Error,
+    /// Bad flags, arguments, request body or query expression; nothing was sent.
+    Usage,
+    /// No token, or the API answered 401/403.
+    Auth,
+    NotFound,
+    /// The API answered 400, 409 or 422: validation errors as well as duplicates.
+    Conflict,
+    RateLimited,
+    /// The API answered 5xx.
+    Server,
+    /// No HTTP response: DNS, connection, TLS or timeout failures.
+    Transport,
+}
+
+impl ExitKind {
+    fn code(self) -> i32 {
+        match self {
+            Self::Error => 1,
+            Self::Usage => 2,
+            Self::Auth => 3,
+            Self::NotFound => 4,
+            Self::Conflict => 5,
+            Self::RateLimited => 6,
+            Self::Server => 7,
+            Self::Transport => 8,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Usage => "usage",
+            Self::Auth => "auth",
+            Self::NotFound => "not_found",
+            Self::Conflict => "conflict",
+            Self::RateLimited => "rate_limited",
+            Self::Server => "server",
+            Self::Transport => "transport",
+        }
+    }
+
+    fn of(err: &anyhow::Error) -> Self {
+        if let Some(classified) = err.downcast_ref::<Classified>() {
+            return classified.0;
+        }
+        if let Some(api) = err.downcast_ref::<ApiError>() {
+            return match api.status {
+                401 | 403 => Self::Auth,
+                404 => Self::NotFound,
+                400 | 409 | 422 => Self::Conflict,
+                429 => Self::RateLimited,
+                500..=599 => Self::Server,
+                _ => Self::Error,
+            };
+        }
+        // Builder errors (a malformed `--header`, say) are the caller's, not the network's.
+        match err.chain().find_map(|cause| cause.downcast_ref::<reqwest::Error>()) {
+            Some(cause) if cause.is_builder() => Self::Usage,
+            Some(_) => Self::Transport,
+            None => Self::Error,
+        }
+    }
+}
+
+/// An error tagged with the exit code it should produce; prints as the error itself.
+#[derive(Debug)]
+struct Classified(ExitKind, anyhow::Error);
+
+impl fmt::Display for Classified {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.1)
+    }
+}
+
+impl std::error::Error for Classified {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.1.chain().nth(1)
+    }
+}
+
+fn usage(err: anyhow::Error) -> anyhow::Error {
+    Classified(ExitKind::Usage, err).into()
+}
+
+fn api_token() -> Result<String> {
+    env::var("CLOUDFLARE_API_TOKEN")
+        .context("CLOUDFLARE_API_TOKEN missing")
+        .map_err(|err| Classified(ExitKind::Auth, err).into())
+}
+
+/// The `--error-format json` report. Every key is always present, `null` when it
+/// does not apply (`status`, `code` and `ray_id` are only known for API errors).
+fn error_envelope(err: &anyhow::Error, kind: ExitKind) -> Value {
+    let api = err.downcast_ref::<ApiError>();
+    json!({
+        "error": {
+            "kind": kind.as_str(),
+            "exit_code": kind.code(),
+            "status": api.map(|api| api.status),
+            "code": api.and_then(ApiError::code),
+            "message": api.map_or_else(|| format!("{err:#}"), ApiError::message),
+            "ray_id": api.and_then(|api| api.ray_id.clone()),
+            "errors": api.map(|api| api.errors.clone()).unwrap_or_default(),
+            "messages": api.map(|api| api.messages.clone()).unwrap_or_default(),
+            "body": api.and_then(|api| api.body.clone()),
+            "fetched": api.and_then(|api| api.fetched),
+        }
+    })
+}
+
 fn run() -> Result<()> {
-    let tree = cloudflare_cli::command_tree::load_command_tree();
+    let args: Vec<OsString> = env::args_os().collect();
+    let tree = load_tree_for(&args)?;
     let cli = build_cli(&tree);
-    let matches = cli.get_matches();
+    let json_errors = global_value(&args, "error-format").as_deref() == Some("json");
+    let matches = match cli.try_get_matches_from(args) {
+        Ok(matches) => matches,
+        // Help and version output, and plain-text usage errors, are clap's (exit 2).
+        Err(err) if !json_errors || !err.use_stderr() || err.kind() == ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand => {
+            err.exit()
+        }
+        Err(err) => {
+            let rendered = err.render().to_string();
+            let message = rendered.lines().next().unwrap_or_default().trim_start_matches("error: ");
+            return Err(usage(anyhow!("{message}")));
+        }
+    };
 
     if let Some(matches) = matches.subcommand_matches("list") {
         return handle_list(&tree, matches);
@@ -26,15 +176,19 @@ fn run() -> Result<()> {
     if let Some(matches) = matches.subcommand_matches("tree") {
         return handle_tree(&tree, matches);
     }
+    if let Some(matches) = matches.subcommand_matches("permissions") {
+        return handle_permissions(&tree, matches);
+    }
     if let Some(matches) = matches.subcommand_matches("api") {
         return handle_api(&tree, matches);
     }
 
-    let token = env::var("CLOUDFLARE_API_TOKEN").context("CLOUDFLARE_API_TOKEN missing")?;
+    let token = api_token()?;
     let endpoint = env::var("CLOUDFLARE_API_URL").unwrap_or_else(|_| tree.endpoint.clone());
 
     let pretty = matches.get_flag("pretty");
     let raw = matches.get_flag("raw");
+    let format = output_format(&matches)?;
     let headers = parse_headers(matches.get_many::<String>("header"));
 
     let (res_name, res_matches) = matches
@@ -44,54 +198,163 @@ fn run() -> Result<()> {
         .subcommand()
         .ok_or_else(|| anyhow!("operation required"))?;
 
-    let op = find_op(&tree, res_name, op_name)
-        .ok_or_else(|| anyhow!("unknown command {res_name} {op_name}"))?;
+    let op = tree
+        .find_op(res_name, op_name)
+        .ok_or_else(|| usage(anyhow!("unknown command {res_name} {op_name}")))?;
+    warn_deprecated(res_name, op, op_matches);
 
-    let (path, query, body, extra_headers) = build_request(&op, op_matches)?;
+    let filter = OutputFilter::from_matches(op_matches).map_err(usage)?;
+    let request = build_request(op, op_matches).map_err(usage)?;
     let mut headers = headers;
-    headers.extend(extra_headers);
+    headers.extend(request.headers.clone());
 
     let method = op.method.parse().context("invalid http method")?;
     let client = HttpClient::new(endpoint, token)?;
-    let response = client.execute(method, &path, &query, &headers, body)?;
-
-    let output = format_output(&response.body, raw)?;
-    write_json_output(output, pretty)?;
+    let output = match PageOptions::from_matches(op_matches, format, &filter).map_err(usage)? {
+        Some(paging) => fetch_all(&client, method, &request, &headers, op.pagination_style(), &paging, raw)?,
+        None => {
+            let response = check_status(client.execute(method, &request.path, &request.query, &headers, request.body)?)?;
+            Some(format_output(&response.body, raw)?)
+        }
+    };
 
-    if response.status >= 400 {
-        return Err(anyhow!("http {}", response.status));
+    if let Some(output) = output {
+        // Schema columns describe `result` items, not the raw envelope.
+        let columns = op
+            .response
+            .as_ref()
+            .filter(|response| response.list && !raw)
+            .map(|response| response.columns.as_slice());
+        let columns = filter.columns(columns);
+        let output = filter.apply(output)?;
+        write_output(&output, format, pretty, columns)?;
     }
 
     Ok(())
 }
 
+/// Deserializes only what the invocation needs: the named resource (plus the
+/// resources holding operations cross-referenced under it) for operation calls,
+/// `describe` and `permissions`, nothing for `api`, and the whole tree for
+/// top-level help, `list`, `tree` and unknown resources (so clap can suggest names).
+fn load_tree_for(args: &[OsString]) -> Result<CommandTree> {
+    let embedded = match (global_value(args, "openapi"), global_value(args, "tree")) {
+        (Some(_), Some(_)) => return Err(usage(anyhow!("--openapi and --tree cannot be used together"))),
+        (Some(spec), None) => cloudflare_cli::tree_cache::load_openapi(&spec)?,
+        (None, Some(path)) => cloudflare_cli::tree_cache::load_tree_file(&path)?,
+        (None, None) => match env::var("CLOUDFLARE_CLI_TREE").ok().filter(|path| !path.is_empty()) {
+            Some(path) => cloudflare_cli::tree_cache::load_tree_file(&path)?,
+            None => cloudflare_cli::embedded::embedded_tree()?,
+        },
+    };
+    let words = leading_words(args, 2);
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+    let partial = match words.as_slice() {
+        ["api", ..] => Some(embedded.load_none()),
+        ["describe" | "permissions", resource, ..] => embedded.load_resource(resource)?,
+        ["list" | "tree" | "describe" | "permissions" | "help", ..] | [] => None,
+        [resource, ..] => embedded.load_resource(resource)?,
+    };
+    match partial {
+        Some(tree) => Ok(tree),
+        None => Ok(embedded.load_all()?),
+    }
+}
+
+/// Value of a global flag, read before clap runs since it decides what clap is given.
+fn global_value(args: &[OsString], long: &str) -> Option<String> {
+    let flag = format!("--{long}");
+    let mut args = args.iter().skip(1).map(|arg| arg.to_string_lossy());
+    let mut found = None;
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
+        }
+        if arg == flag {
+            found = args.next().map(|value| value.into_owned());
+        } else if let Some(value) = arg.strip_prefix(&flag).and_then(|rest| rest.strip_prefix('=')) {
+            found = Some(value.to_string());
+        }
+    }
+    found
+}
+
+/// The first `limit` positional arguments, skipping global flags and their values.
+fn leading_words(args: &[OsString], limit: usize) -> Vec<String> {
+    let valued: Vec<String> = global_args()
+        .iter()
+        .filter(|arg| arg.get_action().takes_values())
+        .filter_map(|arg| arg.get_long().map(|long| format!("--{long}")))
+        .collect();
+    let mut words = Vec::new();
+    let mut args = args.iter().skip(1).map(|arg| arg.to_string_lossy());
+    while words.len() < limit
+        && let Some(arg) = args.next()
+    {
+        if arg == "--" {
+            break;
+        }
+        if valued.iter().any(|flag| *flag == arg) {
+            args.next();
+        } else if !arg.starts_with('-') {
+            words.push(arg.into_owned());
+        }
+    }
+    words
+}
+
+fn global_args() -> Vec<Arg> {
+    vec![
+        Arg::new("pretty")
+            .long("pretty")
+            .global(true)
+            .action(ArgAction::SetTrue)
+            .help("Pretty-print JSON output"),
+        Arg::new("raw")
+            .long("raw")
+            .global(true)
+            .action(ArgAction::SetTrue)
+            .help("Return full API response"),
+        Arg::new("header")
+            .long("header")
+            .global(true)
+            .action(ArgAction::Append)
+            .value_name("NAME:VALUE")
+            .help("Add header (repeatable)"),
+        Arg::new("output")
+            .long("output")
+            .global(true)
+            .value_name("FORMAT")
+            .value_parser(OutputFormat::NAMES)
+            .default_value("json")
+            .help("Output format for API responses"),
+        Arg::new("tree")
+            .long("tree")
+            .global(true)
+            .value_name("PATH")
+            .help("Use this command_tree.json instead of the built-in one [env: CLOUDFLARE_CLI_TREE]"),
+        Arg::new("openapi")
+            .long("openapi")
+            .global(true)
+            .value_name("PATH")
+            .conflicts_with("tree")
+            .help("Build the command tree from this OpenAPI spec (cached by content hash)"),
+        Arg::new("error-format")
+            .long("error-format")
+            .global(true)
+            .value_name("FORMAT")
+            .value_parser(["text", "json"])
+            .default_value("text")
+            .help("How errors are reported on stderr"),
+    ]
+}
+
 fn build_cli(tree: &CommandTree) -> Command {
     let mut cmd = Command::new("cloudflare")
         .about("Cloudflare CLI (OpenAPI-powered)")
         .subcommand_required(true)
         .arg_required_else_help(true)
-        .arg(
-            Arg::new("pretty")
-                .long("pretty")
-                .global(true)
-                .action(ArgAction::SetTrue)
-                .help("Pretty-print JSON output"),
-        )
-        .arg(
-            Arg::new("raw")
-                .long("raw")
-                .global(true)
-                .action(ArgAction::SetTrue)
-                .help("Return full API response"),
-        )
-        .arg(
-            Arg::new("header")
-                .long("header")
-                .global(true)
-                .action(ArgAction::Append)
-                .value_name("NAME:VALUE")
-                .help("Add header (repeatable)"),
-        );
+        .args(global_args());
 
     cmd = cmd.subcommand(
         Command::new("list")
@@ -101,6 +364,12 @@ fn build_cli(tree: &CommandTree) -> Command {
                     .long("json")
                     .action(ArgAction::SetTrue)
                     .help("Emit machine-readable JSON"),
+            )
+            .arg(
+                Arg::new("include-deprecated")
+                    .long("include-deprecated")
+                    .action(ArgAction::SetTrue)
+                    .help("Include deprecated operations"),
             ),
     );
 
@@ -114,16 +383,57 @@ fn build_cli(tree: &CommandTree) -> Command {
                     .long("json")
                     .action(ArgAction::SetTrue)
                     .help("Emit machine-readable JSON"),
+            )
+            .arg(
+                Arg::new("examples")
+                    .long("examples")
+                    .action(ArgAction::SetTrue)
+                    .help("Print runnable example invocations and a sample response"),
+            )
+            .arg(
+                Arg::new("body-template")
+                    .long("body-template")
+                    .action(ArgAction::SetTrue)
+                    .conflicts_with("examples")
+                    .help("Print a request body skeleton (commented YAML, or JSON with --json)"),
+            )
+            .arg(
+                Arg::new("required-only")
+                    .long("required-only")
+                    .action(ArgAction::SetTrue)
+                    .requires("body-template")
+                    .help("Only include required body fields in the skeleton"),
             ),
     );
 
     cmd = cmd.subcommand(
-        Command::new("tree").about("Show full command tree").arg(
-            Arg::new("json")
-                .long("json")
-                .action(ArgAction::SetTrue)
-                .help("Emit machine-readable JSON"),
-        ),
+        Command::new("tree")
+            .about("Show full command tree")
+            .arg(
+                Arg::new("json")
+                    .long("json")
+                    .action(ArgAction::SetTrue)
+                    .help("Emit machine-readable JSON"),
+            )
+            .arg(
+                Arg::new("include-deprecated")
+                    .long("include-deprecated")
+                    .action(ArgAction::SetTrue)
+                    .help("Include deprecated operations"),
+            ),
+    );
+
+    cmd = cmd.subcommand(
+        Command::new("permissions")
+            .about("Show API token permission groups needed by operations")
+            .arg(Arg::new("resource").required(true))
+            .arg(Arg::new("op").required(true).num_args(1..))
+            .arg(
+                Arg::new("json")
+                    .long("json")
+                    .action(ArgAction::SetTrue)
+                    .help("Emit machine-readable JSON"),
+            ),
     );
 
     cmd = cmd.subcommand(
@@ -138,49 +448,46 @@ fn build_cli(tree: &CommandTree) -> Command {
                     .value_name("KEY=VALUE")
                     .help("Query param (repeatable)"),
             )
-            .arg(
-                Arg::new("body")
-                    .long("body")
-                    .value_name("JSON")
-                    .conflicts_with("body-file")
-                    .help("JSON request body"),
-            )
-            .arg(
-                Arg::new("body-file")
-                    .long("body-file")
-                    .value_name("PATH")
-                    .conflicts_with("body")
-                    .help("Read JSON request body from file"),
-            ),
+            .args(payload_args(&PayloadKinds::any()))
+            .args(pagination_args(&BTreeSet::new()))
+            .args(output_filter_args(&BTreeSet::from(["query"]))),
     );
 
+    let mut xrefs = cross_references(tree);
     for resource in &tree.resources {
         let mut res_cmd = Command::new(resource.name.clone())
             .about(resource.display_name.clone())
+            .visible_aliases(resource.aliases.clone())
+            .hide(resource.ops.iter().all(|op| op.deprecated))
             .subcommand_required(true)
             .arg_required_else_help(true);
         for op in &resource.ops {
-            let mut op_cmd = Command::new(op.name.clone()).about(op.display_name.clone());
-            for param in &op.parameters {
-                op_cmd = op_cmd.arg(build_param_arg(param));
+            res_cmd = res_cmd.subcommand(build_op_command(op, op.name.clone()).aliases(op.aliases.clone()));
+        }
+        let taken: BTreeSet<&str> = resource
+            .ops
+            .iter()
+            .flat_map(|op| std::iter::once(&op.name).chain(&op.aliases))
+            .map(String::as_str)
+            .collect();
+        for (name, op) in xrefs.remove(resource.name.as_str()).unwrap_or_default() {
+            if !taken.contains(name) {
+                res_cmd = res_cmd.subcommand(build_op_command(op, name.to_string()).hide(true));
             }
-            if op.has_body {
-                op_cmd = op_cmd.arg(
-                    Arg::new("body")
-                        .long("body")
-                        .value_name("JSON")
-                        .conflicts_with("body-file")
-                        .help("JSON request body"),
-                );
-                op_cmd = op_cmd.arg(
-                    Arg::new("body-file")
-                        .long("body-file")
-                        .value_name("PATH")
-                        .conflicts_with("body")
-                        .help("Read JSON request body from file"),
-                );
+        }
+        cmd = cmd.subcommand(res_cmd);
+    }
+    // Tags whose operations all live elsewhere keep a hidden command so old invocations work.
+    for (res_name, ops) in xrefs {
+        let mut res_cmd = Command::new(res_name.to_string())
+            .hide(true)
+            .subcommand_required(true)
+            .arg_required_else_help(true);
+        let mut seen = BTreeSet::new();
+        for (name, op) in ops {
+            if seen.insert(name) {
+                res_cmd = res_cmd.subcommand(build_op_command(op, name.to_string()).hide(true));
             }
-            res_cmd = res_cmd.subcommand(op_cmd);
         }
         cmd = cmd.subcommand(res_cmd);
     }
@@ -188,32 +495,342 @@ fn build_cli(tree: &CommandTree) -> Command {
     cmd
 }
 
+fn build_op_command(op: &Operation, name: String) -> Command {
+    let mut op_cmd = Command::new(name).about(op.display_name.clone()).hide(op.deprecated);
+    for param in &op.parameters {
+        op_cmd = op_cmd.arg(build_param_arg(param));
+    }
+    if op.has_body {
+        op_cmd = op_cmd.args(payload_args(&PayloadKinds::for_op(op)));
+    }
+    if let Some(body) = &op.body {
+        for field in &body.fields {
+            op_cmd = op_cmd.arg(build_param_arg(field));
+        }
+        if body.accepts_json() && !body.schema.is_null() {
+            op_cmd = op_cmd.arg(
+                Arg::new("no-validate")
+                    .long("no-validate")
+                    .action(ArgAction::SetTrue)
+                    .help("Send the JSON body without checking it against the schema"),
+            );
+        }
+    }
+    let taken: BTreeSet<&str> = op
+        .parameters
+        .iter()
+        .chain(op.body.iter().flat_map(|body| &body.fields))
+        .map(|param| param.flag.as_str())
+        .collect();
+    if op.pagination_style().is_some() {
+        op_cmd = op_cmd.args(pagination_args(&taken));
+    }
+    op_cmd.args(output_filter_args(&taken))
+}
+
+/// `--all`, `--max-items` and `--stream` for paginated operations. `--limit` is an
+/// alias of `--max-items` unless the operation has its own `limit` parameter. Each
+/// flag is left out where one of the operation's parameters already uses it, and
+/// none is added if that is `--all`.
+fn pagination_args(taken: &BTreeSet<&str>) -> Vec<Arg> {
+    if taken.contains("all") {
+        return Vec::new();
+    }
+    let mut args = vec![
+        Arg::new("all")
+            .long("all")
+            .action(ArgAction::SetTrue)
+            .help("Fetch every page and merge the results"),
+    ];
+    if !taken.contains("max-items") {
+        let mut max_items = Arg::new("max-items")
+            .long("max-items")
+            .value_name("N")
+            .requires("all")
+            .value_parser(clap::value_parser!(u64).range(1..))
+            .help("Stop after N items (with --all)");
+        if !taken.contains("limit") {
+            max_items = max_items.visible_alias("limit");
+        }
+        args.push(max_items);
+    }
+    if !taken.contains("stream") {
+        args.push(
+            Arg::new("stream")
+                .long("stream")
+                .action(ArgAction::SetTrue)
+                .requires("all")
+                .conflicts_with("output-query")
+                .help("Print each item as a JSON line as its page arrives (with --all)"),
+        );
+    }
+    args
+}
+
+/// `--output-query` and `--output-fields`, also spelled `--query` and `--fields`
+/// unless the command already has parameters by those names (as the search and
+/// log operations and `api` do).
+fn output_filter_args(taken: &BTreeSet<&str>) -> Vec<Arg> {
+    let query = Arg::new("output-query")
+        .long("output-query")
+        .value_name("EXPR")
+        .help("Filter the output with a JMESPath expression, e.g. '[?proxied].name' or 'length(@)'");
+    let fields = Arg::new("output-fields")
+        .long("output-fields")
+        .value_name("FIELDS")
+        .action(ArgAction::Append)
+        .value_delimiter(',')
+        .help("Only show these fields, in this order (comma-separated dotted paths, e.g. id,name,meta.auto_added)");
+    [(query, "query"), (fields, "fields")]
+        .into_iter()
+        .map(|(arg, alias)| if taken.contains(alias) { arg } else { arg.visible_alias(alias) })
+        .collect()
+}
+
+/// Operations grouped by the other resources that list them (`Operation::also_in`),
+/// each with the name it is reachable by there.
+fn cross_references(tree: &CommandTree) -> BTreeMap<&str, Vec<(&str, &Operation)>> {
+    let mut out: BTreeMap<&str, Vec<(&str, &Operation)>> = BTreeMap::new();
+    for op in tree.resources.iter().flat_map(|res| &res.ops) {
+        for xref in &op.also_in {
+            out.entry(xref.resource.as_str()).or_default().push((xref.name.as_str(), op));
+        }
+    }
+    out
+}
+
+/// Which request payload flags an operation accepts, derived from its body content types.
+struct PayloadKinds {
+    json: bool,
+    multipart: bool,
+    binary: Option<String>,
+}
+
+impl PayloadKinds {
+    fn for_op(op: &Operation) -> Self {
+        match &op.body {
+            Some(body) => Self {
+                json: body.accepts_json(),
+                multipart: body.accepts_multipart(),
+                binary: body.binary_content_type().map(str::to_string),
+            },
+            None => Self {
+                json: true,
+                multipart: false,
+                binary: None,
+            },
+        }
+    }
+
+    fn any() -> Self {
+        Self {
+            json: true,
+            multipart: true,
+            binary: Some("application/octet-stream".to_string()),
+        }
+    }
+}
+
+fn payload_args(kinds: &PayloadKinds) -> Vec<Arg> {
+    let json_ids: &[&str] = if kinds.json { &["body", "body-file"] } else { &[] };
+    let form_ids: &[&str] = if kinds.multipart { &["form", "form-file"] } else { &[] };
+    let binary_ids: &[&str] = if kinds.binary.is_some() { &["data-binary"] } else { &[] };
+
+    let mut args = Vec::new();
+    if kinds.json {
+        let others: Vec<&str> = form_ids.iter().chain(binary_ids).copied().collect();
+        args.push(
+            Arg::new("body")
+                .long("body")
+                .value_name("JSON")
+                .conflicts_with("body-file")
+                .conflicts_with_all(others.clone())
+                .help("JSON request body"),
+        );
+        args.push(
+            Arg::new("body-file")
+                .long("body-file")
+                .value_name("PATH")
+                .conflicts_with("body")
+                .conflicts_with_all(others)
+                .help("Read JSON request body from file"),
+        );
+    }
+    if kinds.multipart {
+        let others: Vec<&str> = json_ids.iter().chain(binary_ids).copied().collect();
+        args.push(
+            Arg::new("form")
+                .long("form")
+                .action(ArgAction::Append)
+                .value_name("NAME=VALUE")
+                .conflicts_with_all(others.clone())
+                .help("Multipart text field (repeatable)"),
+        );
+        args.push(
+            Arg::new("form-file")
+                .long("form-file")
+                .action(ArgAction::Append)
+                .value_name("NAME=@PATH[;type=MIME]")
+                .conflicts_with_all(others)
+                .help("Multipart file field (repeatable)"),
+        );
+    }
+    if kinds.binary.is_some() {
+        let others: Vec<&str> = json_ids.iter().chain(form_ids).copied().collect();
+        args.push(
+            Arg::new("data-binary")
+                .long("data-binary")
+                .value_name("@PATH|DATA")
+                .conflicts_with_all(others)
+                .help("Raw request body, read from a file when prefixed with @"),
+        );
+    }
+    args
+}
+
 fn build_param_arg(param: &ParamDef) -> Arg {
+    let mut help = param.description.clone().unwrap_or_else(|| param.location.clone());
+    if let Some(default) = &param.default {
+        help.push_str(&format!(" [default: {default}]"));
+    }
     let mut arg = Arg::new(param.flag.clone())
         .long(param.flag.clone())
         .value_name(param.name.clone())
-        .help(param.description.clone().unwrap_or_else(|| param.location.clone()));
+        .help(help)
+        .hide(param.deprecated)
+        .value_parser(ParamValueParser::new(param));
 
-    if param.list {
+    if param.list || param.is_query_object() {
         arg = arg.action(ArgAction::Append);
     }
 
     arg
 }
 
+/// Checks a flag value against the parameter's spec constraints (type, enum,
+/// bounds, pattern) so bad input is rejected before any request is sent.
+/// List values are checked item by item after comma splitting.
+#[derive(Clone)]
+struct ParamValueParser {
+    list: bool,
+    schema_type: Option<String>,
+    enum_values: Vec<String>,
+    minimum: Option<f64>,
+    maximum: Option<f64>,
+    exclusive_minimum: bool,
+    exclusive_maximum: bool,
+    pattern: Option<String>,
+    /// Compiled on the first value checked, so building the CLI for thousands
+    /// of operations doesn't compile every pattern in the spec.
+    regex: OnceLock<Option<regex::Regex>>,
+}
+
+impl ParamValueParser {
+    fn new(param: &ParamDef) -> Self {
+        Self {
+            list: param.list && !param.has_json_items(),
+            schema_type: param.schema_type.clone(),
+            enum_values: param.enum_values.clone(),
+            minimum: param.minimum,
+            maximum: param.maximum,
+            exclusive_minimum: param.exclusive_minimum,
+            exclusive_maximum: param.exclusive_maximum,
+            pattern: param.pattern.clone(),
+            regex: OnceLock::new(),
+        }
+    }
+
+    fn regex(&self) -> Option<&regex::Regex> {
+        self.regex
+            .get_or_init(|| {
+                // Spec patterns are ECMA regexes; skip the ones Rust's engine cannot compile.
+                self.pattern.as_deref().and_then(|p| regex::Regex::new(p).ok())
+            })
+            .as_ref()
+    }
+
+    fn check(&self, value: &str) -> Result<(), String> {
+        if !self.enum_values.is_empty() && !self.enum_values.iter().any(|v| v == value) {
+            return Err(format!("expected one of: {}", self.enum_values.join(", ")));
+        }
+        let number = match self.schema_type.as_deref() {
+            Some("integer") => Some(
+                value
+                    .parse::<i64>()
+                    .map_err(|_| "expected an integer".to_string())? as f64,
+            ),
+            Some("number") => Some(value.parse::<f64>().map_err(|_| "expected a number".to_string())?),
+            Some("boolean") => {
+                value.parse::<bool>().map_err(|_| "expected true or false".to_string())?;
+                None
+            }
+            _ => None,
+        };
+        if let Some(number) = number {
+            if let Some(min) = self.minimum {
+                if self.exclusive_minimum && number <= min {
+                    return Err(format!("must be > {min}"));
+                }
+                if number < min {
+                    return Err(format!("must be >= {min}"));
+                }
+            }
+            if let Some(max) = self.maximum {
+                if self.exclusive_maximum && number >= max {
+                    return Err(format!("must be < {max}"));
+                }
+                if number > max {
+                    return Err(format!("must be <= {max}"));
+                }
+            }
+        }
+        if let Some(pattern) = self.regex().filter(|p| !p.is_match(value)) {
+            return Err(format!("must match pattern {}", pattern.as_str()));
+        }
+        Ok(())
+    }
+}
+
+impl TypedValueParser for ParamValueParser {
+    type Value = String;
+
+    fn parse_ref(&self, cmd: &Command, arg: Option<&Arg>, value: &std::ffi::OsStr) -> Result<String, clap::Error> {
+        let value = value
+            .to_str()
+            .ok_or_else(|| clap::Error::new(ErrorKind::InvalidUtf8).with_cmd(cmd))?;
+        let items = if self.list { split_list(value) } else { vec![value.to_string()] };
+        for item in &items {
+            if let Err(reason) = self.check(item) {
+                let flag = arg.and_then(Arg::get_long).unwrap_or_default();
+                let message = format!("invalid value '{item}' for '--{flag}': {reason}\n");
+                return Err(clap::Error::raw(ErrorKind::InvalidValue, message).with_cmd(cmd));
+            }
+        }
+        Ok(value.to_string())
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        if self.enum_values.is_empty() || self.list {
+            return None;
+        }
+        Some(Box::new(self.enum_values.iter().map(PossibleValue::new)))
+    }
+}
+
 fn handle_list(tree: &CommandTree, matches: &clap::ArgMatches) -> Result<()> {
+    let tree = &*visible_tree(tree, matches.get_flag("include-deprecated"));
     if matches.get_flag("json") {
         let mut out = Vec::new();
         for res in &tree.resources {
             let ops: Vec<String> = res.ops.iter().map(|op| op.name.clone()).collect();
-            out.push(json!({"resource": res.name, "display": res.display_name, "ops": ops}));
+            out.push(json!({"resource": res.name, "display": res.display_name, "aliases": res.aliases, "ops": ops}));
         }
         write_stdout_line(&serde_json::to_string_pretty(&out)?)?;
         return Ok(());
     }
 
     for res in &tree.resources {
-        write_stdout_line(&format!("{} ({})", res.name, res.display_name))?;
+        write_stdout_line(&resource_label(res))?;
         for op in &res.ops {
             write_stdout_line(&format!("  {} ({})", op.name, op.display_name))?;
         }
@@ -221,6 +838,14 @@ fn handle_list(tree: &CommandTree, matches: &clap::ArgMatches) -> Result<()> {
     Ok(())
 }
 
+fn resource_label(res: &Resource) -> String {
+    if res.aliases.is_empty() {
+        format!("{} ({})", res.name, res.display_name)
+    } else {
+        format!("{} [{}] ({})", res.name, res.aliases.join(", "), res.display_name)
+    }
+}
+
 fn handle_describe(tree: &CommandTree, matches: &clap::ArgMatches) -> Result<()> {
     let resource = matches
         .get_one::<String>("resource")
@@ -229,8 +854,16 @@ fn handle_describe(tree: &CommandTree, matches: &clap::ArgMatches) -> Result<()>
         .get_one::<String>("op")
         .ok_or_else(|| anyhow!("operation required"))?;
 
-    let op = find_op(tree, resource, op_name)
-        .ok_or_else(|| anyhow!("unknown command {resource} {op_name}"))?;
+    let op = tree
+        .find_op(resource, op_name)
+        .ok_or_else(|| usage(anyhow!("unknown command {resource} {op_name}")))?;
+
+    if matches.get_flag("examples") {
+        return write_examples(resource, op, matches.get_flag("json"));
+    }
+    if matches.get_flag("body-template") {
+        return write_body_template(op, matches.get_flag("required-only"), matches.get_flag("json"));
+    }
 
     if matches.get_flag("json") {
         write_stdout_line(&serde_json::to_string_pretty(op)?)?;
@@ -239,6 +872,23 @@ fn handle_describe(tree: &CommandTree, matches: &clap::ArgMatches) -> Result<()>
 
     write_stdout_line(&format!("{} {}", op.method, op.path))?;
     write_stdout_line(&format!("name: {}", op.display_name))?;
+    if !op.aliases.is_empty() {
+        write_stdout_line(&format!("aliases: {}", op.aliases.join(", ")))?;
+    }
+    if !op.also_in.is_empty() {
+        let places: Vec<String> = op
+            .also_in
+            .iter()
+            .map(|xref| format!("{} {}", xref.resource, xref.name))
+            .collect();
+        write_stdout_line(&format!("also available as: {}", places.join(", ")))?;
+    }
+    if op.deprecated {
+        write_stdout_line("deprecated: true")?;
+    }
+    if !op.permissions.is_empty() {
+        write_stdout_line(&format!("permissions: {}", op.permissions.join(", ")))?;
+    }
     if let Some(summary) = &op.summary {
         write_stdout_line(&format!("summary: {summary}"))?;
     }
@@ -248,23 +898,342 @@ fn handle_describe(tree: &CommandTree, matches: &clap::ArgMatches) -> Result<()>
     if !op.parameters.is_empty() {
         write_stdout_line("params:")?;
         for param in &op.parameters {
+            let deprecated = if param.deprecated { ", deprecated" } else { "" };
+            let style = match (&param.style, param.explode) {
+                (Some(style), Some(explode)) => format!(", style: {style}, explode: {explode}"),
+                (Some(style), None) => format!(", style: {style}"),
+                (None, Some(explode)) => format!(", explode: {explode}"),
+                (None, None) => String::new(),
+            };
             write_stdout_line(&format!(
-                "  --{} ({}, required: {})",
-                param.flag, param.location, param.required
+                "  --{} ({}, {}, required: {}{style}{deprecated}){}",
+                param.flag,
+                param.location,
+                param_type(param),
+                param.required,
+                param_choices(param)
             ))?;
         }
     }
+    if let Some(body) = op.body.as_ref().filter(|body| !body.fields.is_empty()) {
+        write_stdout_line(&format!("body (required: {}):", body.required))?;
+        for field in &body.fields {
+            write_stdout_line(&format!(
+                "  --{} ({}, required: {}){}",
+                field.flag,
+                param_type(field),
+                field.required,
+                param_choices(field)
+            ))?;
+        }
+    }
+    if let Some(response) = &op.response {
+        write_stdout_line(&format!("returns ({}): {}", response.status, shape_label(&response.schema)))?;
+        if !response.columns.is_empty() {
+            write_stdout_line(&format!("  fields: {}", response.columns.join(", ")))?;
+        }
+    }
+    Ok(())
+}
+
+/// A parameter's type as `describe` shows it; lists read `string[]`.
+fn param_type(param: &ParamDef) -> String {
+    let ty = param.schema_type.as_deref().unwrap_or("any");
+    if param.list { format!("{ty}[]") } else { ty.to_string() }
+}
+
+/// The allowed values and default that follow a parameter in `describe`.
+fn param_choices(param: &ParamDef) -> String {
+    let mut out = String::new();
+    if !param.enum_values.is_empty() {
+        out.push_str(&format!(" [{}]", param.enum_values.join(", ")));
+    }
+    if let Some(default) = &param.default {
+        out.push_str(&format!(" [default: {default}]"));
+    }
+    out
+}
+
+fn write_examples(res_name: &str, op: &Operation, json: bool) -> Result<()> {
+    let invocations = example_invocations(res_name, op);
+    let response = op
+        .examples
+        .as_ref()
+        .and_then(|examples| examples.response.as_ref())
+        .map(|response| response.get("result").unwrap_or(response));
+
+    if json {
+        let out = json!({"invocations": invocations, "response": response});
+        write_stdout_line(&serde_json::to_string_pretty(&out)?)?;
+        return Ok(());
+    }
+
+    for invocation in &invocations {
+        write_stdout_line(&invocation.join(" \\\n    "))?;
+        write_stdout_line("")?;
+    }
+    let optional: Vec<String> = op
+        .parameters
+        .iter()
+        .filter(|param| !param.required && param.location != "path")
+        .filter_map(|param| {
+            let value = op.examples.as_ref()?.params.get(&param.name)?;
+            Some(format!("--{} {}", param.flag, shell_quote(&example_arg(value))))
+        })
+        .collect();
+    if !optional.is_empty() {
+        write_stdout_line(&format!("optional: {}", optional.join(" ")))?;
+        write_stdout_line("")?;
+    }
+    if let Some(response) = response {
+        write_stdout_line("sample output:")?;
+        write_stdout_line(&serde_json::to_string_pretty(response)?)?;
+    }
+    Ok(())
+}
+
+/// Builds copy-pasteable command lines (one argument group per element) from the
+/// spec's examples, with `<name>` placeholders for required values it lacks.
+/// A JSON body example yields a `--body` form and, when every key has a flag,
+/// a field-flag form.
+fn example_invocations(res_name: &str, op: &Operation) -> Vec<Vec<String>> {
+    let examples = op.examples.as_ref();
+    let mut base = vec![format!("cloudflare {res_name} {}", op.name)];
+    for param in op.parameters.iter().filter(|param| param.required || param.location == "path") {
+        match examples.and_then(|examples| examples.params.get(&param.name)) {
+            Some(value) => base.push(format!("--{} {}", param.flag, shell_quote(&example_arg(value)))),
+            None => base.push(format!("--{} <{}>", param.flag, param.name)),
+        }
+    }
+
+    let schema = op.body.as_ref().map(|body| &body.schema).filter(|schema| !schema.is_null());
+    let mut body = match (examples.and_then(|examples| examples.body.clone()), &op.body) {
+        (Some(body), _) => body,
+        (None, Some(request_body)) if request_body.required && schema.is_some() => json!({}),
+        _ => return vec![base],
+    };
+    if let Some(schema) = schema {
+        complete_example_body(&mut body, schema);
+    }
+    let mut with_body = base.clone();
+    with_body.push(format!("--body {}", shell_quote(&body.to_string())));
+    let mut invocations = vec![with_body];
+
+    if let (Some(object), Some(request_body)) = (body.as_object(), &op.body) {
+        let flags: Option<Vec<String>> = object
+            .iter()
+            .map(|(name, value)| {
+                let field = request_body.fields.iter().find(|field| &field.name == name)?;
+                Some(format!("--{} {}", field.flag, shell_quote(&example_arg(value))))
+            })
+            .collect();
+        if let Some(flags) = flags.filter(|flags| !flags.is_empty()) {
+            let mut with_flags = base;
+            with_flags.extend(flags);
+            invocations.push(with_flags);
+        }
+    }
+    invocations
+}
+
+/// Adds the required top-level fields a body example leaves out, so the printed
+/// command passes validation: the schema's default or first enum value where it
+/// has one, else a `<name>` placeholder.
+fn complete_example_body(body: &mut Value, schema: &Value) {
+    let Some(object) = body.as_object_mut() else {
+        return;
+    };
+    for (name, property, _) in template_properties(schema, true) {
+        if !object.contains_key(name) {
+            let value = match template_value(property, true, 1) {
+                Value::String(text) if text.is_empty() => Value::String(format!("<{name}>")),
+                other => other,
+            };
+            object.insert(name.clone(), value);
+        }
+    }
+}
+
+fn example_arg(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Array(items) if items.iter().all(|item| !item.is_array() && !item.is_object()) => items
+            .iter()
+            .map(example_arg)
+            .collect::<Vec<_>>()
+            .join(","),
+        other => other.to_string(),
+    }
+}
+
+/// Prints a skeleton of the operation's JSON request body: placeholder values
+/// (default, first enum value, or an empty value of the right type), as plain JSON
+/// or as YAML with a comment per field giving its type, choices and description.
+fn write_body_template(op: &Operation, required_only: bool, json: bool) -> Result<()> {
+    if !op.has_body {
+        return Err(usage(anyhow!("{} takes no request body", op.name)));
+    }
+    let schema = op
+        .body
+        .as_ref()
+        .map(|body| &body.schema)
+        .filter(|schema| !schema.is_null())
+        .ok_or_else(|| usage(anyhow!("no JSON body schema recorded for {}", op.name)))?;
+
+    if json {
+        let skeleton = template_value(schema, required_only, 0);
+        write_stdout_line(&serde_json::to_string_pretty(&skeleton)?)?;
+        return Ok(());
+    }
+    let mut lines = Vec::new();
+    template_yaml(schema, required_only, 0, 0, &mut lines);
+    if lines.is_empty() {
+        // A root without properties (a top-level array, say) has no keys to annotate;
+        // its JSON skeleton is valid YAML.
+        let skeleton = template_value(schema, required_only, 0);
+        lines.push(format!("{}  # {}", serde_json::to_string(&skeleton)?, template_comment(schema, false)));
+    }
+    for line in lines {
+        write_stdout_line(&line)?;
+    }
     Ok(())
 }
 
+const MAX_TEMPLATE_DEPTH: usize = 8;
+
+fn template_value(schema: &Value, required_only: bool, depth: usize) -> Value {
+    let properties = template_properties(schema, required_only);
+    match schema.get("type").and_then(Value::as_str) {
+        _ if depth >= MAX_TEMPLATE_DEPTH => Value::Null,
+        Some("object") | None if !properties.is_empty() => Value::Object(
+            properties
+                .into_iter()
+                .map(|(name, property, _)| (name.clone(), template_value(property, required_only, depth + 1)))
+                .collect(),
+        ),
+        Some("array") => {
+            let item = schema.get("items").map(|items| template_value(items, required_only, depth + 1));
+            Value::Array(item.into_iter().collect())
+        }
+        ty => template_scalar(schema, ty),
+    }
+}
+
+fn template_scalar(schema: &Value, ty: Option<&str>) -> Value {
+    if let Some(value) = schema.get("default") {
+        return value.clone();
+    }
+    if let Some(first) = schema.get("enum").and_then(Value::as_array).and_then(|values| values.first()) {
+        return first.clone();
+    }
+    match ty {
+        Some("string") => Value::String(String::new()),
+        Some("integer") | Some("number") => json!(schema.get("minimum").and_then(Value::as_f64).map_or(0, |min| min as i64)),
+        Some("boolean") => Value::Bool(false),
+        Some("object") => json!({}),
+        _ => Value::Null,
+    }
+}
+
+/// Writable properties of an object schema in spec order, with their required flag.
+fn template_properties(schema: &Value, required_only: bool) -> Vec<(&String, &Value, bool)> {
+    let required: BTreeSet<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|names| names.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .filter(|(_, property)| property.get("readOnly").and_then(Value::as_bool) != Some(true))
+        .map(|(name, property)| (name, property, required.contains(name.as_str())))
+        .filter(|(_, _, required)| *required || !required_only)
+        .collect()
+}
+
+fn template_yaml(schema: &Value, required_only: bool, indent: usize, depth: usize, lines: &mut Vec<String>) {
+    let pad = " ".repeat(indent);
+    for (name, property, required) in template_properties(schema, required_only) {
+        let key = yaml_key(name);
+        let comment = template_comment(property, required);
+        let nested = template_properties(property, required_only);
+        let ty = property.get("type").and_then(Value::as_str);
+        if depth + 1 < MAX_TEMPLATE_DEPTH && matches!(ty, Some("object") | None) && !nested.is_empty() {
+            lines.push(format!("{pad}{key}:  # {comment}"));
+            template_yaml(property, required_only, indent + 2, depth + 1, lines);
+        } else if depth + 1 < MAX_TEMPLATE_DEPTH
+            && ty == Some("array")
+            && let Some(items) = property.get("items").filter(|items| !template_properties(items, required_only).is_empty())
+        {
+            lines.push(format!("{pad}{key}:  # {comment}"));
+            let start = lines.len();
+            template_yaml(items, required_only, indent + 4, depth + 2, lines);
+            if let Some(first) = lines.get_mut(start) {
+                first.replace_range(indent + 2..indent + 4, "- ");
+            }
+        } else {
+            let value = template_value(property, required_only, depth + 1);
+            lines.push(format!("{pad}{key}: {}  # {comment}", serde_json::to_string(&value).unwrap_or_default()));
+        }
+    }
+}
+
+fn template_comment(schema: &Value, required: bool) -> String {
+    let mut parts = vec![shape_label(schema)];
+    if required {
+        parts[0].push_str(", required");
+    }
+    if let Some(values) = schema.get("enum").and_then(Value::as_array).filter(|values| values.len() > 1) {
+        let choices: Vec<String> = values.iter().map(|value| value.as_str().map_or_else(|| value.to_string(), str::to_string)).collect();
+        parts.push(format!("one of: {}", choices.join(", ")));
+    }
+    if let Some(description) = schema
+        .get("description")
+        .and_then(Value::as_str)
+        .and_then(|description| description.lines().map(str::trim).find(|line| !line.is_empty()))
+    {
+        parts.push(description.to_string());
+    }
+    parts.join("; ")
+}
+
+fn yaml_key(name: &str) -> String {
+    let plain = name.chars().next().is_some_and(|ch| ch.is_ascii_alphabetic() || ch == '_')
+        && name.chars().all(|ch| ch.is_ascii_alphanumeric() || "_-.".contains(ch));
+    if plain { name.to_string() } else { Value::String(name.to_string()).to_string() }
+}
+
+fn shell_quote(value: &str) -> String {
+    let plain = !value.is_empty()
+        && value
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || "-_./:@,=+".contains(ch));
+    if plain {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+fn shape_label(schema: &Value) -> String {
+    match schema.get("type").and_then(Value::as_str) {
+        Some("array") => format!("list<{}>", schema.get("items").map(shape_label).unwrap_or_else(|| "any".to_string())),
+        Some(ty) => ty.to_string(),
+        None => "any".to_string(),
+    }
+}
+
 fn handle_tree(tree: &CommandTree, matches: &clap::ArgMatches) -> Result<()> {
+    let tree = &*visible_tree(tree, matches.get_flag("include-deprecated"));
     if matches.get_flag("json") {
         write_stdout_line(&serde_json::to_string_pretty(tree)?)?;
         return Ok(());
     }
 
     for res in &tree.resources {
-        write_stdout_line(&format!("{} ({})", res.name, res.display_name))?;
+        write_stdout_line(&resource_label(res))?;
         for op in &res.ops {
             write_stdout_line(&format!("  {} ({})", op.name, op.display_name))?;
         }
@@ -272,12 +1241,87 @@ fn handle_tree(tree: &CommandTree, matches: &clap::ArgMatches) -> Result<()> {
     Ok(())
 }
 
+fn handle_permissions(tree: &CommandTree, matches: &clap::ArgMatches) -> Result<()> {
+    let resource = matches
+        .get_one::<String>("resource")
+        .ok_or_else(|| anyhow!("resource required"))?;
+    let op_names = matches
+        .get_many::<String>("op")
+        .ok_or_else(|| anyhow!("operation required"))?;
+
+    let mut combined = BTreeSet::new();
+    let mut per_op = serde_json::Map::new();
+    for op_name in op_names {
+        let op = tree
+            .find_op(resource, op_name)
+            .ok_or_else(|| usage(anyhow!("unknown command {resource} {op_name}")))?;
+        if op.permissions.is_empty() {
+            eprintln!("warning: no permission data for {resource} {op_name}");
+        }
+        combined.extend(op.permissions.iter().cloned());
+        per_op.insert(op.name.clone(), json!(op.permissions));
+    }
+
+    if matches.get_flag("json") {
+        let out = json!({"permissions": combined, "operations": per_op});
+        write_stdout_line(&serde_json::to_string_pretty(&out)?)?;
+        return Ok(());
+    }
+
+    for group in &combined {
+        write_stdout_line(group)?;
+    }
+    Ok(())
+}
+
+fn visible_tree(tree: &CommandTree, include_deprecated: bool) -> Cow<'_, CommandTree> {
+    if include_deprecated {
+        return Cow::Borrowed(tree);
+    }
+    let mut tree = tree.clone();
+    for res in &mut tree.resources {
+        res.ops.retain(|op| !op.deprecated);
+    }
+    tree.resources.retain(|res| !res.ops.is_empty());
+    Cow::Owned(tree)
+}
+
+fn warn_deprecated(res_name: &str, op: &Operation, matches: &clap::ArgMatches) {
+    if op.deprecated {
+        let mut warning = format!("warning: {res_name} {} is deprecated", op.name);
+        if let Some(notice) = op.description.as_deref().and_then(replacement_notice) {
+            warning.push_str(&format!(": {notice}"));
+        }
+        eprintln!("{warning}");
+    }
+    let body_fields = op.body.iter().flat_map(|body| body.fields.iter());
+    for param in op.parameters.iter().chain(body_fields) {
+        if param.deprecated && matches.value_source(&param.flag) == Some(ValueSource::CommandLine) {
+            eprintln!("warning: --{} is deprecated", param.flag);
+        }
+    }
+}
+
+/// Picks the sentence of a description that points at a replacement endpoint, if any.
+fn replacement_notice(description: &str) -> Option<String> {
+    const HINTS: &[&str] = &["instead", "replaced by", "in favor of", "in favour of", "migrate to", "use the"];
+    description
+        .split_inclusive(['.', '\n'])
+        .map(str::trim)
+        .find(|sentence| {
+            let lower = sentence.to_ascii_lowercase();
+            HINTS.iter().any(|hint| lower.contains(hint))
+        })
+        .map(str::to_string)
+}
+
 fn handle_api(tree: &CommandTree, matches: &clap::ArgMatches) -> Result<()> {
-    let token = env::var("CLOUDFLARE_API_TOKEN").context("CLOUDFLARE_API_TOKEN missing")?;
+    let token = api_token()?;
     let endpoint = env::var("CLOUDFLARE_API_URL").unwrap_or_else(|_| tree.endpoint.clone());
 
     let pretty = matches.get_flag("pretty");
     let raw = matches.get_flag("raw");
+    let format = output_format(matches)?;
     let headers = parse_headers(matches.get_many::<String>("header"));
 
     let method = matches
@@ -287,23 +1331,200 @@ fn handle_api(tree: &CommandTree, matches: &clap::ArgMatches) -> Result<()> {
         .get_one::<String>("path")
         .ok_or_else(|| anyhow!("path required"))?;
 
-    let query = parse_key_values(matches.get_many::<String>("query"))?;
-    let body = load_body(matches.get_one::<String>("body"), matches.get_one::<String>("body-file"))?;
+    let method: Method = method.parse().map_err(|_| usage(anyhow!("invalid http method {method}")))?;
+    let query = parse_key_values(matches.get_many::<String>("query")).map_err(usage)?;
+    let body = load_payload(&PayloadKinds::any(), matches).map_err(usage)?;
+    let filter = OutputFilter::from_matches(matches).map_err(usage)?;
 
     let client = HttpClient::new(endpoint, token)?;
-    let response = client.execute(method.parse()?, path, &query, &headers, body)?;
-
-    let output = format_output(&response.body, raw)?;
-    write_json_output(output, pretty)?;
+    let output = match PageOptions::from_matches(matches, format, &filter).map_err(usage)? {
+        Some(paging) => {
+            let request = RequestParts {
+                path: path.clone(),
+                query,
+                body,
+                headers: Vec::new(),
+            };
+            // The scheme is read off the first page's `result_info`.
+            fetch_all(&client, method, &request, &headers, None, &paging, raw)?
+        }
+        None => {
+            let response = check_status(client.execute(method, path, &query, &headers, body)?)?;
+            Some(format_output(&response.body, raw)?)
+        }
+    };
 
-    if response.status >= 400 {
-        return Err(anyhow!("http {}", response.status));
+    if let Some(output) = output {
+        let columns = filter.columns(None);
+        let output = filter.apply(output)?;
+        write_output(&output, format, pretty, columns)?;
     }
 
     Ok(())
 }
 
-fn build_request(op: &Operation, matches: &clap::ArgMatches) -> Result<(String, Vec<(String, String)>, Option<Value>, Vec<(String, String)>)> {
+/// What `--all` was asked to do.
+struct PageOptions {
+    max_items: Option<usize>,
+    stream: bool,
+    /// `--fields`, applied to each item as it is streamed.
+    fields: Option<Vec<String>>,
+}
+
+impl PageOptions {
+    fn from_matches(matches: &clap::ArgMatches, format: OutputFormat, filter: &OutputFilter) -> Result<Option<Self>> {
+        // Only paginated commands define `--all`.
+        if !matches.try_get_one::<bool>("all").ok().flatten().copied().unwrap_or(false) {
+            return Ok(None);
+        }
+        // `--max-items` and `--stream` are missing where a parameter took the name.
+        let stream = matches.try_get_one::<bool>("stream").ok().flatten().copied().unwrap_or(false);
+        if stream && !matches!(format, OutputFormat::Json | OutputFormat::Ndjson) {
+            let name = matches.get_one::<String>("output").map_or("json", String::as_str);
+            return Err(anyhow!("--stream prints JSON lines; it cannot be combined with --output {name}"));
+        }
+        Ok(Some(Self {
+            max_items: matches.try_get_one::<u64>("max-items").ok().flatten().map(|n| *n as usize),
+            // A query needs the whole result, so NDJSON is written once it is in.
+            stream: stream || (format == OutputFormat::Ndjson && filter.query.is_none()),
+            fields: filter.fields.clone(),
+        }))
+    }
+}
+
+/// Requests page after page until `result_info` says there are no more (or
+/// `--max-items` is reached), merging the `result` arrays into one, or printing each
+/// item as a JSON line when streaming. With `--raw` the full page bodies are kept
+/// instead. Without a known scheme it is taken from the first page's `result_info`.
+/// Returns the output still to be written; a failed page fails the whole call, and
+/// the error says how far it got.
+fn fetch_all(
+    client: &HttpClient,
+    method: Method,
+    request: &RequestParts,
+    headers: &[(String, String)],
+    style: Option<Pagination>,
+    paging: &PageOptions,
+    raw: bool,
+) -> Result<Option<Value>> {
+    let mut style = style;
+    let mut page: u64 = query_value(&request.query, "page")
+        .and_then(|page| page.parse().ok())
+        .unwrap_or(1);
+    let mut cursor = query_value(&request.query, "cursor").map(str::to_string);
+    let mut collected = Vec::new();
+    let mut items = 0usize;
+    let mut pages = 0usize;
+
+    loop {
+        let mut query = request.query.clone();
+        match style {
+            Some(Pagination::Page) => set_query_value(&mut query, "page", page.to_string()),
+            Some(Pagination::Cursor) => {
+                if let Some(cursor) = &cursor {
+                    set_query_value(&mut query, "cursor", cursor.clone());
+                }
+            }
+            None => {}
+        }
+
+        let response = client
+            .execute(method.clone(), &request.path, &query, headers, request.body.clone())
+            .and_then(check_status)
+            .map_err(|err| after_pages(err, pages, items))?;
+        pages += 1;
+        let result = response.body.get("result").and_then(Value::as_array);
+        if result.is_none() && collected.is_empty() && items == 0 {
+            return Ok(Some(format_output(&response.body, raw)?));
+        }
+        let Some(result) = result else { break };
+        let info = response.body.get("result_info");
+        style = style.or_else(|| info.and_then(pagination_from_info));
+
+        let take = match paging.max_items {
+            Some(max) => result.len().min(max - items),
+            None => result.len(),
+        };
+        let emit = |item: &Value| match &paging.fields {
+            Some(fields) => serde_json::to_string(&select_fields(item, fields)),
+            None => serde_json::to_string(item),
+        };
+        if raw {
+            if paging.stream {
+                write_stdout_line(&emit(&response.body)?)?;
+            } else {
+                collected.push(response.body.clone());
+            }
+        } else {
+            for item in &result[..take] {
+                if paging.stream {
+                    write_stdout_line(&emit(item)?)?;
+                } else {
+                    collected.push(item.clone());
+                }
+            }
+        }
+        items += take;
+
+        if result.is_empty() || paging.max_items.is_some_and(|max| items >= max) {
+            break;
+        }
+        let info_u64 = |key: &str| info.and_then(|info| info.get(key)).and_then(Value::as_u64);
+        match style {
+            Some(Pagination::Page) => {
+                let last = match info_u64("total_pages") {
+                    Some(total) => page >= total,
+                    None => info_u64("per_page").is_none_or(|per_page| (result.len() as u64) < per_page),
+                };
+                if last {
+                    break;
+                }
+                page += 1;
+            }
+            Some(Pagination::Cursor) => {
+                let next = info
+                    .and_then(|info| info.get("cursor").or_else(|| info.pointer("/cursors/after")))
+                    .and_then(Value::as_str)
+                    .filter(|next| !next.is_empty() && cursor.as_deref() != Some(*next));
+                match next {
+                    Some(next) => cursor = Some(next.to_string()),
+                    None => break,
+                }
+            }
+            None => break,
+        }
+    }
+
+    Ok((!paging.stream).then_some(Value::Array(collected)))
+}
+
+fn pagination_from_info(info: &Value) -> Option<Pagination> {
+    if info.get("cursor").is_some() || info.get("cursors").is_some() {
+        Some(Pagination::Cursor)
+    } else if info.get("total_pages").is_some() || info.get("page").is_some() {
+        Some(Pagination::Page)
+    } else {
+        None
+    }
+}
+
+fn query_value<'a>(query: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    query.iter().find(|(name, _)| name == key).map(|(_, value)| value.as_str())
+}
+
+fn set_query_value(query: &mut Vec<(String, String)>, key: &str, value: String) {
+    query.retain(|(name, _)| name != key);
+    query.push((key.to_string(), value));
+}
+
+struct RequestParts {
+    path: String,
+    query: Vec<(String, String)>,
+    body: Option<Body>,
+    headers: Vec<(String, String)>,
+}
+
+fn build_request(op: &Operation, matches: &clap::ArgMatches) -> Result<RequestParts> {
     let mut path = op.path.clone();
     let mut query = Vec::new();
     let mut headers = Vec::new();
@@ -320,25 +1541,196 @@ fn build_request(op: &Operation, matches: &clap::ArgMatches) -> Result<(String,
                 if param.required && values.is_empty() {
                     return Err(anyhow!("missing query param {}", param.name));
                 }
-                for value in values {
-                    query.push((param.name.clone(), value));
-                }
+                query.extend(query_pairs(param, values)?);
             }
             "header" => {
                 let values = resolve_param_values(param, matches)?;
                 if param.required && values.is_empty() {
                     return Err(anyhow!("missing header param {}", param.name));
                 }
-                for value in values {
-                    headers.push((param.name.clone(), value));
+                // Headers only have the `simple` style: one comma-separated value.
+                if !values.is_empty() {
+                    headers.push((param.name.clone(), values.join(",")));
                 }
             }
             _ => {}
         }
     }
 
-    let body = load_body(matches.get_one::<String>("body"), matches.get_one::<String>("body-file"))?;
-    Ok((path, query, body, headers))
+    let body = if op.has_body {
+        load_payload(&PayloadKinds::for_op(op), matches)?
+    } else {
+        None
+    };
+    // Body field flags only feed JSON payloads; forms and raw data are sent as given.
+    let body = match (body, &op.body) {
+        (Some(Body::Json(value)), Some(request_body)) => {
+            merge_body_fields(Some(value), &request_body.fields, matches)?.map(Body::Json)
+        }
+        (None, Some(request_body)) => merge_body_fields(None, &request_body.fields, matches)?.map(Body::Json),
+        (body, _) => body,
+    };
+    if let (Some(Body::Json(value)), Some(request_body)) = (&body, &op.body)
+        && request_body.accepts_json()
+        && !request_body.schema.is_null()
+        && !matches.get_flag("no-validate")
+    {
+        let violations = validate(&request_body.schema, value);
+        if !violations.is_empty() {
+            let lines: Vec<String> = violations.iter().map(|violation| format!("  {violation}")).collect();
+            return Err(anyhow!(
+                "request body does not match the schema (--no-validate to send anyway):\n{}",
+                lines.join("\n")
+            ));
+        }
+    }
+    Ok(RequestParts {
+        path,
+        query,
+        body,
+        headers,
+    })
+}
+
+/// Encodes a query parameter the way the spec declares it. Arrays default to
+/// exploded `form` (one `name=value` per item); `explode: false` joins them with
+/// the style's delimiter, and names ending in `[]` are always repeated. Objects
+/// become `key=value` pairs (exploded form), `name=k1,v1,k2,v2` (form) or
+/// `name[key]=value` (deepObject).
+fn query_pairs(param: &ParamDef, values: Vec<String>) -> Result<Vec<(String, String)>> {
+    let name = &param.name;
+    let style = param.style.as_deref().unwrap_or("form");
+    let explode = param.explode.unwrap_or(style == "form");
+
+    if param.is_query_object() {
+        let objects = values
+            .iter()
+            .map(|value| query_object(param, value))
+            .collect::<Result<Vec<_>>>()?;
+        let mut pairs = Vec::new();
+        if style == "deepObject" {
+            // Arrays of objects are indexed: `filters[0][key]=value`.
+            for (idx, object) in objects.into_iter().enumerate() {
+                let prefix = if param.list { format!("{name}[{idx}]") } else { name.clone() };
+                deep_object_pairs(&prefix, &Value::Object(object), &mut pairs);
+            }
+        } else if param.list {
+            for object in objects {
+                pairs.push((name.clone(), serde_json::to_string(&object)?));
+            }
+        } else if explode {
+            for (key, value) in objects.iter().flatten() {
+                pairs.push((key.clone(), query_scalar(value)));
+            }
+        } else if !objects.is_empty() {
+            let joined: Vec<String> = objects
+                .iter()
+                .flatten()
+                .flat_map(|(key, value)| [key.clone(), query_scalar(value)])
+                .collect();
+            pairs.push((name.clone(), joined.join(",")));
+        }
+        return Ok(pairs);
+    }
+
+    if !param.list || values.is_empty() || explode || name.ends_with("[]") {
+        return Ok(values.into_iter().map(|value| (name.clone(), value)).collect());
+    }
+    let delimiter = match style {
+        "spaceDelimited" => " ",
+        "pipeDelimited" => "|",
+        _ => ",",
+    };
+    Ok(vec![(name.clone(), values.join(delimiter))])
+}
+
+/// Parses an object parameter value: a JSON object, or comma-separated `KEY=VALUE` pairs.
+fn query_object(param: &ParamDef, value: &str) -> Result<serde_json::Map<String, Value>> {
+    if value.trim_start().starts_with('{') {
+        return serde_json::from_str(value).with_context(|| format!("--{} expects a JSON object", param.flag));
+    }
+    let mut object = serde_json::Map::new();
+    for item in split_list(value) {
+        let (key, value) = split_key_value(&item)
+            .ok_or_else(|| anyhow!("--{} expects KEY=VALUE pairs, got {item}", param.flag))?;
+        object.insert(key.trim().to_string(), Value::String(value.trim().to_string()));
+    }
+    Ok(object)
+}
+
+fn deep_object_pairs(prefix: &str, value: &Value, out: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object {
+                deep_object_pairs(&format!("{prefix}[{key}]"), value, out);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                deep_object_pairs(&format!("{prefix}[]"), item, out);
+            }
+        }
+        value => out.push((prefix.to_string(), query_scalar(value))),
+    }
+}
+
+fn query_scalar(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn merge_body_fields(body: Option<Value>, fields: &[ParamDef], matches: &clap::ArgMatches) -> Result<Option<Value>> {
+    let mut values = serde_json::Map::new();
+    for field in fields {
+        let raw = resolve_param_values(field, matches)?;
+        if raw.is_empty() {
+            continue;
+        }
+        let value = if field.list {
+            let mut items = Vec::new();
+            for item in &raw {
+                // `--rules '[{...},{...}]'` gives the whole list at once, `--rules '{...}'`
+                // one item; for lists of arrays only an array of arrays is the whole list.
+                let whole_list = |list: &[Value]| field.schema_type.as_deref() == Some("object") || list.iter().all(Value::is_array);
+                match parse_typed_value(field, item)? {
+                    Value::Array(list) if field.has_json_items() && whole_list(&list) => items.extend(list),
+                    value => items.push(value),
+                }
+            }
+            Value::Array(items)
+        } else {
+            parse_typed_value(field, &raw[0])?
+        };
+        values.insert(field.name.clone(), value);
+    }
+
+    if values.is_empty() {
+        return Ok(body);
+    }
+    match body {
+        None => Ok(Some(Value::Object(values))),
+        Some(Value::Object(mut map)) => {
+            map.extend(values);
+            Ok(Some(Value::Object(map)))
+        }
+        Some(_) => Err(anyhow!("--body must be a JSON object when combined with field flags")),
+    }
+}
+
+fn parse_typed_value(param: &ParamDef, raw: &str) -> Result<Value> {
+    let invalid = || anyhow!("invalid {} value for --{}: {raw}", param.schema_type.as_deref().unwrap_or("string"), param.flag);
+    match param.schema_type.as_deref() {
+        Some("integer") => raw.parse::<i64>().map(Value::from).map_err(|_| invalid()),
+        Some("number") => match serde_json::from_str::<Value>(raw) {
+            Ok(value @ Value::Number(_)) => Ok(value),
+            _ => Err(invalid()),
+        },
+        Some("boolean") => raw.parse::<bool>().map(Value::Bool).map_err(|_| invalid()),
+        Some("object") | Some("array") => serde_json::from_str(raw).map_err(|_| invalid()),
+        _ => Ok(Value::String(raw.to_string())),
+    }
 }
 
 fn resolve_param_value(param: &ParamDef, matches: &clap::ArgMatches) -> Result<Option<String>> {
@@ -352,11 +1744,22 @@ fn resolve_param_value(param: &ParamDef, matches: &clap::ArgMatches) -> Result<O
 }
 
 fn resolve_param_values(param: &ParamDef, matches: &clap::ArgMatches) -> Result<Vec<String>> {
+    // Object values are split into pairs by `query_object`.
+    if param.is_query_object() {
+        return Ok(matches
+            .get_many::<String>(&param.flag)
+            .map(|values| values.cloned().collect())
+            .unwrap_or_default());
+    }
     if param.list {
         let mut values = Vec::new();
         if let Some(items) = matches.get_many::<String>(&param.flag) {
             for item in items {
-                values.extend(split_list(item));
+                if param.has_json_items() {
+                    values.push(item.clone());
+                } else {
+                    values.extend(split_list(item));
+                }
             }
         }
         return Ok(values);
@@ -384,6 +1787,57 @@ fn split_list(value: &str) -> Vec<String> {
     }
 }
 
+fn load_payload(kinds: &PayloadKinds, matches: &clap::ArgMatches) -> Result<Option<Body>> {
+    if kinds.multipart {
+        let mut parts = Vec::new();
+        for value in matches.get_many::<String>("form").into_iter().flatten() {
+            let (name, text) = value
+                .split_once('=')
+                .ok_or_else(|| anyhow!("--form expects NAME=VALUE, got {value}"))?;
+            parts.push(FormPart {
+                name: name.to_string(),
+                value: FormValue::Text(text.to_string()),
+                content_type: None,
+            });
+        }
+        for value in matches.get_many::<String>("form-file").into_iter().flatten() {
+            let (name, spec) = value
+                .split_once('=')
+                .ok_or_else(|| anyhow!("--form-file expects NAME=@PATH, got {value}"))?;
+            let spec = spec.strip_prefix('@').unwrap_or(spec);
+            let (path, content_type) = match spec.split_once(";type=") {
+                Some((path, content_type)) => (path, Some(content_type.to_string())),
+                None => (spec, None),
+            };
+            parts.push(FormPart {
+                name: name.to_string(),
+                value: FormValue::File(path.into()),
+                content_type,
+            });
+        }
+        if !parts.is_empty() {
+            return Ok(Some(Body::Multipart(parts)));
+        }
+    }
+    if let Some(content_type) = &kinds.binary
+        && let Some(value) = matches.get_one::<String>("data-binary")
+    {
+        let data = match value.strip_prefix('@') {
+            Some(path) => fs::read(path).with_context(|| format!("read data file {path}"))?,
+            None => value.as_bytes().to_vec(),
+        };
+        return Ok(Some(Body::Binary {
+            content_type: content_type.clone(),
+            data,
+        }));
+    }
+    if kinds.json {
+        let body = load_body(matches.get_one::<String>("body"), matches.get_one::<String>("body-file"))?;
+        return Ok(body.map(Body::Json));
+    }
+    Ok(None)
+}
+
 fn load_body(body: Option<&String>, body_file: Option<&String>) -> Result<Option<Value>> {
     if let Some(raw) = body {
         let value = serde_json::from_str(raw).context("invalid JSON body")?;
@@ -431,6 +1885,31 @@ fn split_key_value(value: &str) -> Option<(&str, &str)> {
     None
 }
 
+/// Notes on a failed page how many pages and items `--all` had already fetched
+/// (and, when streaming, printed).
+fn after_pages(err: anyhow::Error, pages: usize, items: usize) -> anyhow::Error {
+    if pages == 0 {
+        return err;
+    }
+    let fetched = Fetched { pages, items };
+    match err.downcast::<ApiError>() {
+        Ok(api) => ApiError {
+            fetched: Some(fetched),
+            ..api
+        }
+        .into(),
+        Err(err) => Classified(ExitKind::of(&err), anyhow!("{err} ({fetched})")).into(),
+    }
+}
+
+/// Turns a 4xx/5xx response into an `ApiError`.
+fn check_status(response: ResponseData) -> Result<ResponseData> {
+    if response.status >= 400 {
+        return Err(ApiError::from_response(&response).into());
+    }
+    Ok(response)
+}
+
 fn format_output(body: &Value, raw: bool) -> Result<Value> {
     if raw {
         return Ok(body.clone());
@@ -441,20 +1920,57 @@ fn format_output(body: &Value, raw: bool) -> Result<Value> {
     Ok(body.clone())
 }
 
-fn write_json_output(value: Value, pretty: bool) -> Result<()> {
-    if pretty {
-        write_stdout_line(&serde_json::to_string_pretty(&value)?)?;
-    } else {
-        write_stdout_line(&serde_json::to_string(&value)?)?;
+/// `--query` and `--fields`, applied in that order to what would be printed.
+struct OutputFilter {
+    query: Option<Query>,
+    fields: Option<Vec<String>>,
+}
+
+impl OutputFilter {
+    fn from_matches(matches: &clap::ArgMatches) -> Result<Self> {
+        let query = matches
+            .get_one::<String>("output-query")
+            .map(|expr| Query::parse(expr).map_err(|err| anyhow!("invalid query {expr:?}: {err}")))
+            .transpose()?;
+        let fields = matches
+            .get_many::<String>("output-fields")
+            .map(|fields| fields.map(|field| field.trim().to_string()).filter(|field| !field.is_empty()).collect::<Vec<_>>())
+            .filter(|fields| !fields.is_empty());
+        Ok(Self { query, fields })
+    }
+
+    fn apply(&self, output: Value) -> Result<Value> {
+        let output = match &self.query {
+            Some(query) => query.apply(&output)?,
+            None => output,
+        };
+        Ok(match &self.fields {
+            Some(fields) => select_fields(&output, fields),
+            None => output,
+        })
+    }
+
+    /// Table columns: the selected fields, else the schema's unless a query reshaped
+    /// the output.
+    fn columns<'a>(&'a self, schema: Option<&'a [String]>) -> Option<&'a [String]> {
+        match (&self.fields, &self.query) {
+            (Some(fields), _) => Some(fields),
+            (None, Some(_)) => None,
+            (None, None) => schema,
+        }
     }
-    Ok(())
 }
 
-fn find_op<'a>(tree: &'a CommandTree, res_name: &str, op_name: &str) -> Option<&'a Operation> {
-    tree.resources
-        .iter()
-        .find(|res| res.name == res_name)
-        .and_then(|res| res.ops.iter().find(|op| op.name == op_name))
+fn output_format(matches: &clap::ArgMatches) -> Result<OutputFormat> {
+    OutputFormat::parse(matches.get_one::<String>("output").map(String::as_str).unwrap_or("json"))
+}
+
+fn write_output(value: &Value, format: OutputFormat, pretty: bool, columns: Option<&[String]>) -> Result<()> {
+    let text = render(value, format, pretty, columns)?;
+    if !text.is_empty() {
+        write_stdout_line(&text)?;
+    }
+    Ok(())
 }
 
 fn write_stdout_line(line: &str) -> Result<()> {
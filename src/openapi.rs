@@ -1,16 +1,65 @@
 use anyhow::{Context, Result};
+use serde_json::{Map as JsonMap, Value as JsonValue};
 use serde_yaml::Value;
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 
-use crate::command_tree::{CommandTree, Operation, ParamDef, Resource};
+use crate::command_tree::{
+    CommandTree, CrossRef, Operation, OperationExamples, Pagination, ParamDef, RequestBody, Resource, ResponseSchema,
+};
+use crate::lint::{LintKind, LintReport};
+
+const MAX_SCHEMA_DEPTH: usize = 32;
+/// How deep response shapes are kept; deeper levels are reduced to their `type`.
+const MAX_SHAPE_DEPTH: usize = 4;
+/// Flags the CLI itself defines on operations: global flags, payload, pagination and
+/// output filter flags. Parameters by these names are given a `param-` prefix and
+/// body fields a `body-` prefix.
+const RESERVED_FLAGS: &[&str] = &[
+    "all",
+    "body",
+    "body-file",
+    "data-binary",
+    "error-format",
+    "form",
+    "form-file",
+    "header",
+    "help",
+    "max-items",
+    "no-validate",
+    "openapi",
+    "output",
+    "output-fields",
+    "output-query",
+    "pretty",
+    "raw",
+    "stream",
+    "tree",
+];
+
+/// Parses an OpenAPI document in either JSON or YAML form.
+pub fn parse_spec(raw: &str) -> Result<Value> {
+    if raw.trim_start().starts_with('{') {
+        let json: JsonValue = serde_json::from_str(raw).context("parse openapi json")?;
+        return serde_yaml::to_value(json).context("convert openapi json");
+    }
+    serde_yaml::from_str(raw).context("parse openapi yaml")
+}
 
 pub fn build_command_tree(doc: &Value) -> Result<CommandTree> {
+    lint_spec(doc).map(|(tree, _)| tree)
+}
+
+/// Builds the command tree and reports what had to be worked around on the way:
+/// path templates that disagree with their parameters, flag and name collisions,
+/// and parameters that could not be read.
+pub fn lint_spec(doc: &Value) -> Result<(CommandTree, LintReport)> {
+    let mut report = LintReport::default();
     let endpoint = doc
         .get("servers")
         .and_then(Value::as_sequence)
         .and_then(|servers| servers.first())
         .and_then(Value::as_mapping)
-        .and_then(|server| server.get(&Value::String("url".into())))
+        .and_then(|server| server.get(Value::String("url".into())))
         .and_then(Value::as_str)
         .unwrap_or("https://api.cloudflare.com/client/v4")
         .to_string();
@@ -18,7 +67,7 @@ pub fn build_command_tree(doc: &Value) -> Result<CommandTree> {
     let version = doc
         .get("info")
         .and_then(Value::as_mapping)
-        .and_then(|info| info.get(&Value::String("version".into())))
+        .and_then(|info| info.get(Value::String("version".into())))
         .and_then(Value::as_str)
         .and_then(parse_major_version)
         .unwrap_or(4);
@@ -29,9 +78,15 @@ pub fn build_command_tree(doc: &Value) -> Result<CommandTree> {
         .context("openapi missing paths")?;
 
     let mut resources: BTreeMap<String, Resource> = BTreeMap::new();
+    let mut long_names: BTreeMap<String, HashSet<String>> = BTreeMap::new();
+    let mut legacy_names = Vec::new();
+    let mut short_names: BTreeMap<String, Vec<String>> = BTreeMap::new();
     let methods = [
         "get", "post", "put", "patch", "delete", "options", "head",
     ];
+    // Names that resources derived from paths must not take.
+    let mut taken_resources = tagged_resource_names(paths, &methods);
+    taken_resources.extend(BUILTIN_COMMANDS.iter().map(|name| name.to_string()));
 
     for (path_value, path_item) in paths {
         let path = path_value
@@ -42,73 +97,357 @@ pub fn build_command_tree(doc: &Value) -> Result<CommandTree> {
             .as_mapping()
             .context("path item must be mapping")?;
 
-        let path_params = collect_parameters(path_map.get(&Value::String("parameters".into())));
+        let mut skipped = Vec::new();
+        let path_params = collect_parameters(doc, path_map.get(Value::String("parameters".into())), &mut skipped);
+        for detail in skipped {
+            report.push(LintKind::SkippedParam, &path, None, None, detail);
+        }
 
         for method in methods {
-            let op_value = match path_map.get(&Value::String(method.into())) {
+            let op_value = match path_map.get(Value::String(method.into())) {
                 Some(value) => value,
                 None => continue,
             };
             let op_map = op_value.as_mapping().context("op must be mapping")?;
-            let op_id = op_map
-                .get(&Value::String("operationId".into()))
+            let operation_id = op_map
+                .get(Value::String("operationId".into()))
                 .and_then(Value::as_str)
-                .map(str::to_string)
+                .map(str::to_string);
+            let op_id = operation_id
+                .clone()
                 .unwrap_or_else(|| format!("{method}_{path}"));
 
             let summary = op_map
-                .get(&Value::String("summary".into()))
+                .get(Value::String("summary".into()))
                 .and_then(Value::as_str)
                 .map(str::to_string);
             let description = op_map
-                .get(&Value::String("description".into()))
+                .get(Value::String("description".into()))
                 .and_then(Value::as_str)
                 .map(str::to_string);
 
-            let op_params = collect_parameters(op_map.get(&Value::String("parameters".into())));
+            let deprecated = op_map
+                .get(Value::String("deprecated".into()))
+                .and_then(Value::as_bool)
+                .unwrap_or(false);
+
+            let permissions = collect_permissions(op_map);
+            let examples = collect_examples(doc, path_map, op_map);
+
+            let mut skipped = Vec::new();
+            let op_params = collect_parameters(doc, op_map.get(Value::String("parameters".into())), &mut skipped);
+            for detail in skipped {
+                report.push(LintKind::SkippedParam, &path, Some(method), Some(&op_id), detail);
+            }
             let parameters = merge_parameters(path_params.clone(), op_params);
 
-            let has_body = op_map.get(&Value::String("requestBody".into())).is_some();
+            let request_body = op_map.get(Value::String("requestBody".into()));
+            let has_body = request_body.is_some();
+            let body = request_body.and_then(|value| parse_request_body(doc, value, &parameters));
+            lint_operation(&path, method, &op_id, &parameters, body.as_ref(), &mut report);
+            let response = op_map
+                .get(Value::String("responses".into()))
+                .and_then(|responses| parse_response(doc, responses));
+            let pagination = op_map
+                .get(Value::String("responses".into()))
+                .and_then(|responses| detect_pagination(doc, method, &parameters, responses));
 
-            let tags = op_map
-                .get(&Value::String("tags".into()))
-                .and_then(Value::as_sequence)
-                .cloned()
-                .unwrap_or_default();
+            let tags = op_tags(op_map);
+            // Untagged operations get a resource derived from their path so that every
+            // path in the spec is reachable as a generated command.
+            let (res_name, display_name, path_derived) = match home_tag(&tags, &op_id) {
+                Some(tag) => (normalize_name(tag), tag.clone(), false),
+                None => {
+                    let derived = path_resource_name(&path);
+                    let name = (1..)
+                        .map(|idx| if idx == 1 { derived.clone() } else { format!("{derived}-{idx}") })
+                        .find(|name| !taken_resources.contains(name))
+                        .unwrap_or_default();
+                    if name != derived {
+                        report.push(
+                            LintKind::ResourceNameCollision,
+                            &path,
+                            Some(method),
+                            Some(&op_id),
+                            format!("resource `{derived}` is a tag's or a built-in command's, renamed to `{name}`"),
+                        );
+                    }
+                    (name.clone(), name, true)
+                }
+            };
+            // Before operations were stored once, every tag held its own copy named
+            // after the operationId and suffixed on a clash within that tag; those
+            // names stay reachable and are checked once the tree is built.
+            let mut also_in = Vec::new();
+            for other in tags.iter().map(|other| normalize_name(other)) {
+                if other == res_name || also_in.iter().any(|xref: &CrossRef| xref.resource == other) {
+                    continue;
+                }
+                let names = long_names.entry(other.clone()).or_default();
+                let name = unique_op_name(names, &normalize_name(&op_id), method);
+                names.insert(name.clone());
+                legacy_names.push((other.clone(), name.clone(), method, path.clone(), op_id.clone()));
+                also_in.push(CrossRef { resource: other, name });
+            }
+            also_in.sort();
 
-            for tag_value in tags {
-                let tag = match tag_value.as_str() {
-                    Some(t) => t.to_string(),
-                    None => continue,
-                };
-                let res_name = normalize_name(&tag);
-                let resource = resources.entry(res_name.clone()).or_insert_with(|| Resource {
-                    name: res_name,
-                    display_name: tag.clone(),
-                    ops: Vec::new(),
-                });
-
-                let op_name = unique_op_name(resource, &normalize_name(&op_id), method);
-                resource.ops.push(Operation {
-                    name: op_name,
-                    display_name: op_id.clone(),
-                    method: method.to_uppercase(),
-                    path: path.clone(),
-                    summary: summary.clone(),
-                    description: description.clone(),
-                    parameters: parameters.clone(),
-                    has_body,
-                });
-            }
-        }
-    }
-
-    let resources = resources.into_values().collect();
-    Ok(CommandTree {
+            let resource = resources.entry(res_name.clone()).or_insert_with(|| Resource {
+                name: res_name.clone(),
+                display_name,
+                aliases: Vec::new(),
+                path_derived,
+                ops: Vec::new(),
+            });
+            resource.path_derived &= path_derived;
+
+            // The full operationId-based name stays reachable as an alias; it is
+            // deduplicated against earlier long names only, exactly as before.
+            let names = long_names.entry(res_name.clone()).or_default();
+            let long_name = unique_op_name(names, &normalize_name(&op_id), method);
+            names.insert(long_name.clone());
+            legacy_names.push((res_name.clone(), long_name.clone(), method, path.clone(), op_id.clone()));
+            if long_name != normalize_name(&op_id) {
+                report.push(
+                    LintKind::OpNameCollision,
+                    &path,
+                    Some(method),
+                    Some(&op_id),
+                    format!("long name `{res_name} {}` taken, renamed to `{long_name}`", normalize_name(&op_id)),
+                );
+            }
+
+            // Short names are given once every long name is known (see
+            // `assign_short_names`); until then operations go by their long name.
+            let short_name = match &operation_id {
+                Some(op_id) => short_op_name(&res_name, op_id, method, &path),
+                None => method_verb(method, &path).to_string(),
+            };
+            short_names.entry(res_name.clone()).or_default().push(short_name);
+
+            resource.ops.push(Operation {
+                name: long_name,
+                aliases: Vec::new(),
+                display_name: op_id.clone(),
+                method: method.to_uppercase(),
+                path: path.clone(),
+                summary,
+                description,
+                parameters,
+                has_body,
+                body,
+                response,
+                permissions,
+                also_in,
+                examples,
+                deprecated: deprecated || (!tags.is_empty() && tags.iter().all(|tag| is_deprecated_tag(tag))),
+                pagination,
+                path_derived,
+            });
+        }
+    }
+
+    assign_short_names(&mut resources, short_names, &long_names, &mut report);
+    let mut resources: Vec<Resource> = resources.into_values().collect();
+    assign_resource_aliases(&mut resources);
+    let tree = CommandTree {
         version,
         endpoint,
         resources,
-    })
+    };
+    for (res_name, name, method, path, op_id) in legacy_names {
+        let resolves = tree
+            .find_op(&res_name, &name)
+            .is_some_and(|op| op.path == path && op.method.eq_ignore_ascii_case(method));
+        if !resolves {
+            report.push(
+                LintKind::LostLegacyName,
+                &path,
+                Some(method),
+                Some(&op_id),
+                format!("`{res_name} {name}` now resolves to another operation or none"),
+            );
+        }
+    }
+    Ok((tree, report))
+}
+
+/// Checks one operation's path template against its path parameters, and its
+/// parameters and body fields for flags that collide with each other or the CLI's own.
+fn lint_operation(
+    path: &str,
+    method: &str,
+    op_id: &str,
+    parameters: &[ParamDef],
+    body: Option<&RequestBody>,
+    report: &mut LintReport,
+) {
+    let placeholders = path_placeholders(path);
+    let path_params: HashSet<&str> = parameters
+        .iter()
+        .filter(|param| param.location == "path")
+        .map(|param| param.name.as_str())
+        .collect();
+    for placeholder in &placeholders {
+        if !path_params.contains(placeholder) {
+            report.push(
+                LintKind::MissingPathParam,
+                path,
+                Some(method),
+                Some(op_id),
+                format!("`{{{placeholder}}}` has no path parameter"),
+            );
+        }
+    }
+    for name in &path_params {
+        if !placeholders.contains(name) {
+            report.push(
+                LintKind::UnusedPathParam,
+                path,
+                Some(method),
+                Some(op_id),
+                format!("path parameter `{name}` is not in the template"),
+            );
+        }
+    }
+
+    for param in parameters.iter().chain(body.iter().flat_map(|body| &body.fields)) {
+        let wanted = normalize_flag(&param.name);
+        if param.flag != wanted && RESERVED_FLAGS.contains(&wanted.as_str()) {
+            report.push(
+                LintKind::ReservedFlag,
+                path,
+                Some(method),
+                Some(op_id),
+                format!("{} `{}`: --{wanted} is the CLI's own, renamed to --{}", param.location, param.name, param.flag),
+            );
+        }
+    }
+
+    let mut flags: BTreeMap<&str, &ParamDef> = BTreeMap::new();
+    for param in parameters {
+        if let Some(other) = flags.insert(param.flag.as_str(), param) {
+            report.push(
+                LintKind::DuplicateFlag,
+                path,
+                Some(method),
+                Some(op_id),
+                format!(
+                    "--{} used by {} `{}` and {} `{}`",
+                    param.flag, other.location, other.name, param.location, param.name
+                ),
+            );
+        }
+    }
+    // Body properties whose flag was empty or already taken are left out of `fields`.
+    if let Some(body) = body
+        && let Some(properties) = body.schema.get("properties").and_then(JsonValue::as_object)
+    {
+        for name in properties.keys() {
+            if !body.fields.iter().any(|field| field.name == *name) {
+                report.push(
+                    LintKind::DuplicateFlag,
+                    path,
+                    Some(method),
+                    Some(op_id),
+                    format!("body field `{name}` has no flag: --{} is taken", normalize_flag(name)),
+                );
+            }
+        }
+    }
+}
+
+/// Names of the `{placeholder}` segments of a path template, in order.
+fn path_placeholders(path: &str) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut rest = path;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else { break };
+        out.push(&rest[start + 1..start + len]);
+        rest = &rest[start + len + 1..];
+    }
+    out
+}
+
+/// Picks the single resource an operation lives under: the tag its operationId is
+/// prefixed with, else the first non-deprecated tag, else the first tag.
+fn home_tag<'a>(tags: &'a [String], op_id: &str) -> Option<&'a String> {
+    let op_id = normalize_name(op_id);
+    tags.iter()
+        .filter(|tag| {
+            let name = normalize_name(tag);
+            op_id.strip_prefix(&name).is_some_and(|rest| rest.starts_with('-'))
+        })
+        .max_by_key(|tag| normalize_name(tag).len())
+        .or_else(|| tags.iter().find(|tag| !is_deprecated_tag(tag)))
+        .or_else(|| tags.first())
+}
+
+fn op_tags(op_map: &serde_yaml::Mapping) -> Vec<String> {
+    op_map
+        .get(Value::String("tags".into()))
+        .and_then(Value::as_sequence)
+        .map(|tags| tags.iter().filter_map(Value::as_str).map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// Names of the resources tagged operations live under (see `home_tag`).
+fn tagged_resource_names(paths: &serde_yaml::Mapping, methods: &[&str]) -> HashSet<String> {
+    let mut out = HashSet::new();
+    for (path, path_item) in paths {
+        for method in methods {
+            let Some(op_map) = path_item.get(*method).and_then(Value::as_mapping) else {
+                continue;
+            };
+            let op_id = match op_map.get(Value::String("operationId".into())).and_then(Value::as_str) {
+                Some(op_id) => op_id.to_string(),
+                None => format!("{method}_{}", path.as_str().unwrap_or_default()),
+            };
+            if let Some(tag) = home_tag(&op_tags(op_map), &op_id) {
+                out.insert(normalize_name(tag));
+            }
+        }
+    }
+    out
+}
+
+/// Builds a resource name from a path's literal segments, singularizing collections
+/// addressed by an id: `/zones/{zone_id}/hold` becomes `zone-hold`.
+fn path_resource_name(path: &str) -> String {
+    let segments: Vec<&str> = path.split('/').filter(|segment| !segment.is_empty()).collect();
+    let mut words = Vec::new();
+    for (idx, segment) in segments.iter().enumerate() {
+        if segment.starts_with('{') {
+            continue;
+        }
+        let segment = normalize_name(segment);
+        let addressed = segments.get(idx + 1).is_some_and(|next| next.starts_with('{'));
+        words.push(if addressed { singular(&segment).to_string() } else { segment });
+    }
+    let name = words.join("-");
+    if name.is_empty() { "root".to_string() } else { name }
+}
+
+/// Tags such as `access-bookmark-applications-deprecated` mark every operation under them.
+fn is_deprecated_tag(tag: &str) -> bool {
+    normalize_name(tag).split('-').any(|word| word == "deprecated")
+}
+
+/// Reads the API token permission groups from the `x-api-token-group` extension,
+/// falling back to the `x-cfPermissionsRequired` enum.
+fn collect_permissions(op_map: &serde_yaml::Mapping) -> Vec<String> {
+    let groups = op_map.get(Value::String("x-api-token-group".into())).or_else(|| {
+        op_map
+            .get(Value::String("x-cfPermissionsRequired".into()))
+            .and_then(|required| required.get("enum"))
+    });
+    let mut out: Vec<String> = match groups {
+        Some(Value::Sequence(items)) => items.iter().filter_map(Value::as_str).map(str::to_string).collect(),
+        Some(Value::String(group)) => vec![group.clone()],
+        _ => Vec::new(),
+    };
+    out.sort();
+    out.dedup();
+    out
 }
 
 fn parse_major_version(input: &str) -> Option<u32> {
@@ -118,72 +457,426 @@ fn parse_major_version(input: &str) -> Option<u32> {
         .and_then(|s| s.parse::<u32>().ok())
 }
 
-fn collect_parameters(value: Option<&Value>) -> Vec<ParamDef> {
+/// Reads a `parameters` list; entries that cannot be used are described in `skipped`.
+fn collect_parameters(doc: &Value, value: Option<&Value>, skipped: &mut Vec<String>) -> Vec<ParamDef> {
     let mut out = Vec::new();
     let Some(list) = value.and_then(Value::as_sequence) else {
         return out;
     };
 
     for item in list {
-        let Some(map) = item.as_mapping() else {
+        let Some(map) = resolve_ref(doc, item).and_then(Value::as_mapping) else {
+            let reference = item.get("$ref").and_then(Value::as_str).unwrap_or("parameter");
+            skipped.push(format!("{reference} does not resolve to a parameter object"));
             continue;
         };
         let name = map
-            .get(&Value::String("name".into()))
+            .get(Value::String("name".into()))
             .and_then(Value::as_str)
             .map(str::to_string);
         let location = map
-            .get(&Value::String("in".into()))
+            .get(Value::String("in".into()))
             .and_then(Value::as_str)
             .map(str::to_string);
 
         let (name, location) = match (name, location) {
             (Some(n), Some(l)) => (n, l),
-            _ => continue,
+            (Some(n), None) => {
+                skipped.push(format!("parameter `{n}` has no `in`"));
+                continue;
+            }
+            (None, _) => {
+                skipped.push("parameter without `name`".to_string());
+                continue;
+            }
         };
 
         let required = map
-            .get(&Value::String("required".into()))
+            .get(Value::String("required".into()))
             .and_then(Value::as_bool)
             .unwrap_or(false);
+        let schema = map
+            .get(Value::String("schema".into()))
+            .map(|schema| resolve_schema(doc, schema))
+            .unwrap_or(JsonValue::Null);
         let description = map
-            .get(&Value::String("description".into()))
+            .get(Value::String("description".into()))
             .and_then(Value::as_str)
             .map(str::to_string);
+        let deprecated = map
+            .get(Value::String("deprecated".into()))
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        let style = map
+            .get(Value::String("style".into()))
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        let explode = map.get(Value::String("explode".into())).and_then(Value::as_bool);
 
-        let schema = map.get(&Value::String("schema".into()));
-        let (schema_type, list) = parse_schema(schema);
-
-        out.push(ParamDef {
-            name: name.clone(),
-            flag: normalize_flag(&name),
-            location,
-            required,
-            list,
-            schema_type,
-            description,
-        });
+        let mut flag = normalize_flag(&name);
+        if RESERVED_FLAGS.contains(&flag.as_str()) {
+            flag = format!("param-{flag}");
+        }
+        let mut param = param_from_schema(name, flag, location, required, &schema);
+        param.description = description.or(param.description);
+        param.deprecated |= deprecated;
+        param.style = style;
+        param.explode = explode;
+        out.push(param);
     }
 
     out
 }
 
-fn parse_schema(value: Option<&Value>) -> (Option<String>, bool) {
-    let Some(schema) = value.and_then(Value::as_mapping) else {
-        return (None, false);
+fn parse_request_body(doc: &Value, value: &Value, parameters: &[ParamDef]) -> Option<RequestBody> {
+    let body = resolve_ref(doc, value)?;
+    let required = body.get("required").and_then(Value::as_bool).unwrap_or(false);
+    let content_types = body
+        .get("content")
+        .and_then(Value::as_mapping)
+        .map(|content| {
+            content
+                .keys()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    let schema = body
+        .get("content")
+        .and_then(|content| content.get("application/json"))
+        .and_then(|media| media.get("schema"))
+        .map(|schema| resolve_schema(doc, schema))
+        .unwrap_or(JsonValue::Null);
+
+    let mut taken: HashSet<String> = parameters.iter().map(|param| param.flag.clone()).collect();
+    taken.extend(RESERVED_FLAGS.iter().map(|flag| flag.to_string()));
+
+    let required_fields: HashSet<&str> = schema
+        .get("required")
+        .and_then(JsonValue::as_array)
+        .map(|items| items.iter().filter_map(JsonValue::as_str).collect())
+        .unwrap_or_default();
+
+    let mut fields = Vec::new();
+    if let Some(properties) = schema.get("properties").and_then(JsonValue::as_object) {
+        for (name, property) in properties {
+            let mut flag = normalize_flag(name);
+            if flag.is_empty() {
+                continue;
+            }
+            if taken.contains(&flag) {
+                flag = format!("body-{flag}");
+            }
+            if !taken.insert(flag.clone()) {
+                continue;
+            }
+            let required = required_fields.contains(name.as_str());
+            fields.push(param_from_schema(name.clone(), flag, "body".to_string(), required, property));
+        }
+    }
+
+    Some(RequestBody {
+        required,
+        content_types,
+        fields,
+        schema,
+    })
+}
+
+/// Reads a numeric bound and whether it is exclusive. OpenAPI 3.0 marks the
+/// inclusive bound exclusive with a boolean; 3.1 (JSON Schema) gives the
+/// exclusive bound as its own number.
+fn bound(schema: &JsonValue, inclusive: &str, exclusive: &str) -> (Option<f64>, bool) {
+    let limit = schema.get(inclusive).and_then(JsonValue::as_f64);
+    match schema.get(exclusive) {
+        Some(JsonValue::Bool(exclusive)) => (limit, *exclusive && limit.is_some()),
+        Some(value) => match value.as_f64() {
+            // With both forms present, keep whichever bound is tighter.
+            Some(strict) => {
+                let inclusive_tighter = |limit: f64| match inclusive {
+                    "minimum" => limit > strict,
+                    _ => limit < strict,
+                };
+                match limit {
+                    Some(limit) if inclusive_tighter(limit) => (Some(limit), false),
+                    _ => (Some(strict), true),
+                }
+            }
+            None => (limit, false),
+        },
+        None => (limit, false),
+    }
+}
+
+fn param_from_schema(name: String, flag: String, location: String, required: bool, schema: &JsonValue) -> ParamDef {
+    let (schema_type, list) = parse_schema(schema);
+    // Constraints on array parameters apply to each item.
+    let constrained = match schema.get("items") {
+        Some(items) if list => items,
+        _ => schema,
     };
-    let schema_type = schema
-        .get(&Value::String("type".into()))
-        .and_then(Value::as_str)
-        .map(str::to_string);
+    let default = match schema.get("default") {
+        Some(JsonValue::Array(items)) => {
+            let items: Vec<String> = items.iter().filter_map(scalar_to_string).collect();
+            (!items.is_empty()).then(|| items.join(","))
+        }
+        Some(value) => scalar_to_string(value),
+        None => None,
+    };
+    let (minimum, exclusive_minimum) = bound(constrained, "minimum", "exclusiveMinimum");
+    let (maximum, exclusive_maximum) = bound(constrained, "maximum", "exclusiveMaximum");
+    ParamDef {
+        name,
+        flag,
+        location,
+        required,
+        list,
+        schema_type,
+        description: schema_str(schema, "description"),
+        enum_values: schema_enum(schema),
+        default,
+        minimum,
+        maximum,
+        exclusive_minimum,
+        exclusive_maximum,
+        pattern: schema_str(constrained, "pattern"),
+        deprecated: schema.get("deprecated").and_then(JsonValue::as_bool).unwrap_or(false),
+        style: None,
+        explode: None,
+    }
+}
+
+/// Resolves the first 2xx JSON response and records the shape of its `result`
+/// (or of the whole body for endpoints without the usual envelope).
+fn parse_response(doc: &Value, responses: &Value) -> Option<ResponseSchema> {
+    let (status, response) = success_response(doc, responses)?;
+    let schema = json_media(response)?
+        .get("schema")
+        .map(|schema| resolve_schema(doc, schema))?;
+    let result = schema
+        .get("properties")
+        .and_then(|properties| properties.get("result"))
+        .unwrap_or(&schema);
+
+    let list = schema_str(result, "type").as_deref() == Some("array");
+    let item = match result.get("items") {
+        Some(items) if list => items,
+        _ => result,
+    };
+    let columns = item
+        .get("properties")
+        .and_then(JsonValue::as_object)
+        .map(|properties| {
+            properties
+                .iter()
+                .filter(|(_, property)| {
+                    !matches!(schema_str(property, "type").as_deref(), Some("object" | "array"))
+                })
+                .map(|(name, _)| name.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Some(ResponseSchema {
+        status,
+        list,
+        columns,
+        schema: schema_shape(result, 0),
+    })
+}
+
+/// Recognizes list operations that page through their results: a `cursor` query
+/// parameter or `result_info.cursor` means cursor paging, `page` or
+/// `result_info.total_pages` means numbered pages.
+fn detect_pagination(doc: &Value, method: &str, parameters: &[ParamDef], responses: &Value) -> Option<Pagination> {
+    if method != "get" {
+        return None;
+    }
+    let has_query = |name: &str| parameters.iter().any(|param| param.location == "query" && param.name == name);
+    let (_, response) = success_response(doc, responses)?;
+    let schema = json_media(response)
+        .and_then(|media| media.get("schema"))
+        .map(|schema| resolve_schema(doc, schema))
+        .unwrap_or(JsonValue::Null);
+    let list = schema
+        .pointer("/properties/result/type")
+        .and_then(JsonValue::as_str)
+        == Some("array");
+    let info = schema.pointer("/properties/result_info/properties");
+    let has_info = |name: &str| info.and_then(|info| info.get(name)).is_some();
+
+    if has_query("cursor") || has_info("cursor") || has_info("cursors") {
+        Some(Pagination::Cursor)
+    } else if has_query("page") || (list && has_info("total_pages")) {
+        Some(Pagination::Page)
+    } else {
+        None
+    }
+}
+
+/// The first 2xx response, by status code.
+fn success_response<'a>(doc: &'a Value, responses: &'a Value) -> Option<(String, &'a Value)> {
+    let mut success: Vec<(String, &Value)> = responses
+        .as_mapping()?
+        .iter()
+        .filter_map(|(status, response)| {
+            let status = match status {
+                Value::Number(n) => n.to_string(),
+                other => other.as_str()?.to_string(),
+            };
+            status.starts_with('2').then_some((status, response))
+        })
+        .collect();
+    success.sort_by(|a, b| a.0.cmp(&b.0));
+    let (status, response) = success.into_iter().next()?;
+    Some((status, resolve_ref(doc, response)?))
+}
+
+fn json_media(value: &Value) -> Option<&Value> {
+    value
+        .get("content")
+        .and_then(|content| content.get("application/json"))
+}
+
+/// Gathers spec examples for parameters, the JSON request body and the success
+/// response. Explicit `example`/`examples` win; otherwise examples are assembled
+/// from the schema's property-level examples.
+fn collect_examples(doc: &Value, path_map: &serde_yaml::Mapping, op_map: &serde_yaml::Mapping) -> Option<OperationExamples> {
+    let mut params = JsonMap::new();
+    let lists = [
+        path_map.get(Value::String("parameters".into())),
+        op_map.get(Value::String("parameters".into())),
+    ];
+    for item in lists.into_iter().flatten().filter_map(Value::as_sequence).flatten() {
+        let Some(param) = resolve_ref(doc, item) else {
+            continue;
+        };
+        let Some(name) = param.get("name").and_then(Value::as_str) else {
+            continue;
+        };
+        let example = media_example(doc, param).or_else(|| {
+            param
+                .get("schema")
+                .and_then(|schema| schema_example(&resolve_schema(doc, schema), 0))
+        });
+        if let Some(example) = example {
+            params.insert(name.to_string(), example);
+        }
+    }
+
+    let body = op_map
+        .get(Value::String("requestBody".into()))
+        .and_then(|body| resolve_ref(doc, body))
+        .and_then(json_media)
+        .and_then(|media| media_example(doc, media).or_else(|| media_schema_example(doc, media, "readOnly")));
+
+    let response = op_map
+        .get(Value::String("responses".into()))
+        .and_then(|responses| success_response(doc, responses))
+        .and_then(|(_, response)| json_media(response))
+        .and_then(|media| media_example(doc, media).or_else(|| media_schema_example(doc, media, "writeOnly")));
+
+    if params.is_empty() && body.is_none() && response.is_none() {
+        return None;
+    }
+    Some(OperationExamples {
+        params,
+        body,
+        response,
+    })
+}
+
+/// `example`, or the first of `examples`, on a parameter or media type object.
+fn media_example(doc: &Value, value: &Value) -> Option<JsonValue> {
+    if let Some(example) = value.get("example") {
+        return Some(to_json(doc, example));
+    }
+    let examples = value.get("examples")?.as_mapping()?;
+    let first = resolve_ref(doc, examples.values().next()?)?;
+    first.get("value").map(|example| to_json(doc, example))
+}
+
+fn media_schema_example(doc: &Value, media: &Value, skip: &str) -> Option<JsonValue> {
+    media
+        .get("schema")
+        .and_then(|schema| schema_example_for(&resolve_schema(doc, schema), 0, Some(skip)))
+}
+
+fn to_json(doc: &Value, value: &Value) -> JsonValue {
+    inline_schema(doc, value, &mut Vec::new(), Position::Data)
+}
+
+fn schema_example(schema: &JsonValue, depth: usize) -> Option<JsonValue> {
+    schema_example_for(schema, depth, None)
+}
+
+/// `skip` names the `readOnly`/`writeOnly` marker of properties that do not belong
+/// in this direction (read-only fields are not sent, write-only ones not returned).
+fn schema_example_for(schema: &JsonValue, depth: usize, skip: Option<&str>) -> Option<JsonValue> {
+    if let Some(example) = schema.get("example") {
+        return Some(example.clone());
+    }
+    if let Some(example) = schema.get("examples").and_then(JsonValue::as_array).and_then(|items| items.first()) {
+        return Some(example.clone());
+    }
+    if depth >= MAX_SCHEMA_DEPTH {
+        return None;
+    }
+    // A union's first variant gives a consistent example; the merged view mixes variants.
+    for key in ["oneOf", "anyOf"] {
+        if let Some(example) = schema
+            .get(key)
+            .and_then(JsonValue::as_array)
+            .and_then(|variants| variants.first())
+            .and_then(|variant| schema_example_for(variant, depth + 1, skip))
+        {
+            return Some(example);
+        }
+    }
+    if let Some(items) = schema.get("items") {
+        return schema_example_for(items, depth + 1, skip).map(|item| JsonValue::Array(vec![item]));
+    }
+    let properties = schema.get("properties")?.as_object()?;
+    let object: JsonMap<String, JsonValue> = properties
+        .iter()
+        .filter(|(_, property)| skip.is_none_or(|marker| property.get(marker) != Some(&JsonValue::Bool(true))))
+        .filter_map(|(name, property)| Some((name.clone(), schema_example_for(property, depth + 1, skip)?)))
+        .collect();
+    (!object.is_empty()).then_some(JsonValue::Object(object))
+}
+
+/// Strips a resolved schema down to its structure (types, properties, items, enums).
+fn schema_shape(schema: &JsonValue, depth: usize) -> JsonValue {
+    let mut out = JsonMap::new();
+    for key in ["type", "format", "enum", "nullable"] {
+        if let Some(value) = schema.get(key) {
+            out.insert(key.to_string(), value.clone());
+        }
+    }
+    if depth >= MAX_SHAPE_DEPTH {
+        return JsonValue::Object(out);
+    }
+    if let Some(items) = schema.get("items") {
+        out.insert("items".to_string(), schema_shape(items, depth + 1));
+    }
+    if let Some(properties) = schema.get("properties").and_then(JsonValue::as_object) {
+        let properties = properties
+            .iter()
+            .map(|(name, property)| (name.clone(), schema_shape(property, depth + 1)))
+            .collect();
+        out.insert("properties".to_string(), JsonValue::Object(properties));
+    }
+    JsonValue::Object(out)
+}
+
+fn parse_schema(schema: &JsonValue) -> (Option<String>, bool) {
+    let schema_type = schema_str(schema, "type");
     let list = schema_type.as_deref() == Some("array");
     let schema_type = if list {
         schema
-            .get(&Value::String("items".into()))
-            .and_then(Value::as_mapping)
-            .and_then(|items| items.get(&Value::String("type".into())))
-            .and_then(Value::as_str)
-            .map(str::to_string)
+            .get("items")
+            .and_then(|items| schema_str(items, "type"))
             .or(Some("array".to_string()))
     } else {
         schema_type
@@ -191,6 +884,311 @@ fn parse_schema(value: Option<&Value>) -> (Option<String>, bool) {
     (schema_type, list)
 }
 
+fn schema_str(schema: &JsonValue, key: &str) -> Option<String> {
+    schema.get(key).and_then(JsonValue::as_str).map(str::to_string)
+}
+
+fn schema_enum(schema: &JsonValue) -> Vec<String> {
+    let schema = match schema.get("items") {
+        Some(items) if schema.get("enum").is_none() => items,
+        _ => schema,
+    };
+    schema
+        .get("enum")
+        .and_then(JsonValue::as_array)
+        .map(|values| values.iter().filter_map(scalar_to_string).collect())
+        .unwrap_or_default()
+}
+
+fn scalar_to_string(value: &JsonValue) -> Option<String> {
+    match value {
+        JsonValue::String(s) => Some(s.clone()),
+        JsonValue::Number(n) => Some(n.to_string()),
+        JsonValue::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// Inlines local `$ref`s and flattens `allOf` into a self-contained JSON schema.
+/// Recursive references are cut off and replaced with an empty schema.
+pub fn resolve_schema(doc: &Value, value: &Value) -> JsonValue {
+    let mut stack = Vec::new();
+    inline_schema(doc, value, &mut stack, Position::Schema)
+}
+
+/// What a YAML node is, as far as schema handling goes: only real schema objects get
+/// `allOf` merging and normalization, never a `properties` map or example data.
+#[derive(Clone, Copy, PartialEq)]
+enum Position {
+    Schema,
+    SchemaMap,
+    Data,
+}
+
+fn child_position(parent: Position, key: &str) -> Position {
+    match (parent, key) {
+        (Position::SchemaMap, _) => Position::Schema,
+        (Position::Schema, "properties" | "patternProperties" | "$defs" | "definitions") => Position::SchemaMap,
+        (
+            Position::Schema,
+            "items" | "additionalProperties" | "not" | "anyOf" | "oneOf" | "prefixItems" | "contains",
+        ) => Position::Schema,
+        _ => Position::Data,
+    }
+}
+
+fn inline_schema(doc: &Value, value: &Value, stack: &mut Vec<String>, position: Position) -> JsonValue {
+    if let Some(reference) = value.get("$ref").and_then(Value::as_str) {
+        if stack.len() >= MAX_SCHEMA_DEPTH || stack.iter().any(|seen| seen == reference) {
+            return JsonValue::Object(JsonMap::new());
+        }
+        let Some(target) = lookup_pointer(doc, reference) else {
+            return JsonValue::Object(JsonMap::new());
+        };
+        stack.push(reference.to_string());
+        let out = inline_schema(doc, target, stack, position);
+        stack.pop();
+        return out;
+    }
+
+    match value {
+        Value::Mapping(map) => {
+            let mut out = JsonMap::new();
+            for (key, item) in map {
+                let Some(key) = key.as_str() else {
+                    continue;
+                };
+                if key == "allOf" && position == Position::Schema {
+                    continue;
+                }
+                out.insert(key.to_string(), inline_schema(doc, item, stack, child_position(position, key)));
+            }
+            if position != Position::Schema {
+                return JsonValue::Object(out);
+            }
+            for member in all_of(value) {
+                if let JsonValue::Object(member) = inline_schema(doc, member, stack, Position::Schema) {
+                    merge_schema(&mut out, member);
+                }
+            }
+            normalize_schema(&mut out);
+            JsonValue::Object(out)
+        }
+        Value::Sequence(items) => JsonValue::Array(
+            items
+                .iter()
+                .map(|item| inline_schema(doc, item, stack, position))
+                .collect(),
+        ),
+        Value::Tagged(tagged) => inline_schema(doc, &tagged.value, stack, position),
+        other => serde_json::to_value(other).unwrap_or(JsonValue::Null),
+    }
+}
+
+/// Collapses OpenAPI 3.1 and union constructs so every schema has at most one string
+/// `type`: `type: [string, "null"]` and `nullable` become `nullable: true`, `const`
+/// becomes a one-value `enum`, and `anyOf`/`oneOf` variants contribute their common
+/// type, the union of their properties and enums, and the fields they all require.
+fn normalize_schema(schema: &mut JsonMap<String, JsonValue>) {
+    if let Some(JsonValue::Array(types)) = schema.get("type") {
+        let types: Vec<JsonValue> = types.clone();
+        let non_null: Vec<&JsonValue> = types.iter().filter(|ty| ty.as_str() != Some("null")).collect();
+        if non_null.len() < types.len() {
+            schema.insert("nullable".to_string(), JsonValue::Bool(true));
+        }
+        match non_null.as_slice() {
+            [single] => {
+                schema.insert("type".to_string(), (*single).clone());
+            }
+            _ => {
+                schema.remove("type");
+            }
+        }
+    }
+
+    if let Some(value) = schema.get("const").cloned() {
+        schema
+            .entry("enum")
+            .or_insert_with(|| JsonValue::Array(vec![value.clone()]));
+        if !schema.contains_key("type")
+            && let Some(ty) = json_type(&value)
+        {
+            schema.insert("type".to_string(), JsonValue::String(ty.to_string()));
+        }
+    }
+
+    let mut variants = Vec::new();
+    for key in ["anyOf", "oneOf"] {
+        if let Some(JsonValue::Array(items)) = schema.get(key) {
+            variants.extend(items.iter().filter_map(JsonValue::as_object).cloned());
+        }
+    }
+    let (nulls, variants): (Vec<_>, Vec<_>) = variants
+        .into_iter()
+        .partition(|variant| variant.get("type").and_then(JsonValue::as_str) == Some("null"));
+    if !nulls.is_empty() {
+        schema.insert("nullable".to_string(), JsonValue::Bool(true));
+    }
+    if !variants.is_empty() {
+        merge_variants(schema, &variants);
+    }
+}
+
+fn merge_variants(schema: &mut JsonMap<String, JsonValue>, variants: &[JsonMap<String, JsonValue>]) {
+    if !schema.contains_key("type") {
+        let types: BTreeSet<&str> = variants
+            .iter()
+            .filter_map(|variant| variant.get("type").and_then(JsonValue::as_str))
+            .collect();
+        if let (1, Some(ty)) = (types.len(), types.first()) {
+            schema.insert("type".to_string(), JsonValue::String(ty.to_string()));
+        }
+    }
+
+    if !schema.contains_key("enum") && variants.iter().all(|variant| variant.contains_key("enum")) {
+        let mut values: Vec<JsonValue> = Vec::new();
+        for value in variants.iter().filter_map(|variant| variant.get("enum")).filter_map(JsonValue::as_array).flatten() {
+            if !values.contains(value) {
+                values.push(value.clone());
+            }
+        }
+        schema.insert("enum".to_string(), JsonValue::Array(values));
+    }
+
+    if !schema.contains_key("items")
+        && let Some(items) = variants.iter().find_map(|variant| variant.get("items"))
+    {
+        schema.insert("items".to_string(), items.clone());
+    }
+
+    let mut properties = match schema.remove("properties") {
+        Some(JsonValue::Object(properties)) => properties,
+        _ => JsonMap::new(),
+    };
+    let own_properties: HashSet<String> = properties.keys().cloned().collect();
+    for variant in variants {
+        let Some(JsonValue::Object(extra)) = variant.get("properties") else {
+            continue;
+        };
+        for (name, property) in extra {
+            match properties.get_mut(name) {
+                None => {
+                    properties.insert(name.clone(), property.clone());
+                }
+                Some(JsonValue::Object(existing)) if !own_properties.contains(name) => {
+                    if let (Some(JsonValue::Array(values)), Some(JsonValue::Array(more))) =
+                        (existing.get_mut("enum"), property.get("enum"))
+                    {
+                        for value in more {
+                            if !values.contains(value) {
+                                values.push(value.clone());
+                            }
+                        }
+                    }
+                }
+                Some(_) => {}
+            }
+        }
+    }
+    if !properties.is_empty() {
+        schema.insert("properties".to_string(), JsonValue::Object(properties));
+    }
+
+    if !schema.contains_key("required") {
+        let mut required: Option<Vec<JsonValue>> = None;
+        for variant in variants {
+            let names = variant
+                .get("required")
+                .and_then(JsonValue::as_array)
+                .cloned()
+                .unwrap_or_default();
+            required = Some(match required {
+                None => names,
+                Some(current) => current.into_iter().filter(|name| names.contains(name)).collect(),
+            });
+        }
+        if let Some(required) = required.filter(|required| !required.is_empty()) {
+            schema.insert("required".to_string(), JsonValue::Array(required));
+        }
+    }
+}
+
+fn json_type(value: &JsonValue) -> Option<&'static str> {
+    match value {
+        JsonValue::String(_) => Some("string"),
+        JsonValue::Number(n) if n.is_i64() || n.is_u64() => Some("integer"),
+        JsonValue::Number(_) => Some("number"),
+        JsonValue::Bool(_) => Some("boolean"),
+        JsonValue::Array(_) => Some("array"),
+        JsonValue::Object(_) => Some("object"),
+        JsonValue::Null => None,
+    }
+}
+
+fn merge_schema(target: &mut JsonMap<String, JsonValue>, member: JsonMap<String, JsonValue>) {
+    for (key, value) in member {
+        match (key.as_str(), target.get_mut(&key), value) {
+            ("properties", Some(JsonValue::Object(existing)), JsonValue::Object(extra)) => {
+                for (name, schema) in extra {
+                    existing.entry(name).or_insert(schema);
+                }
+            }
+            ("required", Some(JsonValue::Array(existing)), JsonValue::Array(extra)) => {
+                for name in extra {
+                    if !existing.contains(&name) {
+                        existing.push(name);
+                    }
+                }
+            }
+            (_, Some(_), _) => {}
+            (_, None, value) => {
+                target.insert(key, value);
+            }
+        }
+    }
+}
+
+fn all_of(schema: &Value) -> &[Value] {
+    schema
+        .get("allOf")
+        .and_then(Value::as_sequence)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+/// Follows a chain of local `$ref`s (`#/components/...`) to the value it points at.
+/// Returns `None` for unresolvable or cyclic references.
+fn resolve_ref<'a>(doc: &'a Value, value: &'a Value) -> Option<&'a Value> {
+    let mut current = value;
+    let mut seen = HashSet::new();
+    while let Some(reference) = current.get("$ref").and_then(Value::as_str) {
+        if !seen.insert(reference) {
+            return None;
+        }
+        current = lookup_pointer(doc, reference)?;
+    }
+    Some(current)
+}
+
+fn lookup_pointer<'a>(doc: &'a Value, reference: &str) -> Option<&'a Value> {
+    let pointer = reference.strip_prefix('#')?;
+    if pointer.is_empty() {
+        return Some(doc);
+    }
+    let mut current = doc;
+    for token in pointer.strip_prefix('/')?.split('/') {
+        let token = token.replace("~1", "/").replace("~0", "~");
+        let token = urlencoding::decode(&token)
+            .map(|t| t.into_owned())
+            .unwrap_or(token);
+        current = match current {
+            Value::Sequence(items) => items.get(token.parse::<usize>().ok()?)?,
+            _ => current.get(token.as_str())?,
+        };
+    }
+    Some(current)
+}
+
 fn merge_parameters(base: Vec<ParamDef>, override_params: Vec<ParamDef>) -> Vec<ParamDef> {
     let mut map: BTreeMap<(String, String), ParamDef> = BTreeMap::new();
     for param in base {
@@ -221,23 +1219,287 @@ fn normalize_flag(input: &str) -> String {
     normalize_name(input).replace("--", "-")
 }
 
-fn unique_op_name(resource: &Resource, base: &str, method: &str) -> String {
-    let existing: HashSet<&str> = resource.ops.iter().map(|op| op.name.as_str()).collect();
+fn unique_op_name(existing: &HashSet<String>, base: &str, method: &str) -> String {
     if !existing.contains(base) {
         return base.to_string();
     }
 
     let candidate = format!("{base}-{method}");
-    if !existing.contains(candidate.as_str()) {
+    if !existing.contains(&candidate) {
         return candidate;
     }
 
     let mut idx = 2;
     loop {
         let next = format!("{candidate}-{idx}");
-        if !existing.contains(next.as_str()) {
+        if !existing.contains(&next) {
             return next;
         }
         idx += 1;
     }
 }
+
+/// Renames each operation to its short name and keeps the long name as an alias.
+/// Short names steer clear of every long name the resource had in the old tree,
+/// including those of operations cross-referenced into it, so old invocations
+/// keep reaching the same operation.
+fn assign_short_names(
+    resources: &mut BTreeMap<String, Resource>,
+    mut short_names: BTreeMap<String, Vec<String>>,
+    long_names: &BTreeMap<String, HashSet<String>>,
+    report: &mut LintReport,
+) {
+    for (res_name, resource) in resources.iter_mut() {
+        let mut taken = long_names.get(res_name).cloned().unwrap_or_default();
+        let shorts = short_names.remove(res_name).unwrap_or_default();
+        for (op, short_name) in resource.ops.iter_mut().zip(shorts) {
+            let long_name = std::mem::take(&mut op.name);
+            taken.remove(&long_name);
+            // Short names drop words on purpose, so a clash is only worth a lint
+            // when neither the path nor the operationId tells the two apart.
+            let (op_name, numbered) = unique_short_op_name(&taken, &short_name, res_name, &op.display_name, &op.path);
+            if numbered {
+                report.push(
+                    LintKind::OpNameCollision,
+                    &op.path,
+                    Some(&op.method),
+                    Some(&op.display_name),
+                    format!("name `{res_name} {short_name}` taken, renamed to `{op_name}`"),
+                );
+            }
+            taken.insert(op_name.clone());
+            if long_name != op_name {
+                taken.insert(long_name.clone());
+                op.aliases.push(long_name);
+            }
+            op.name = op_name;
+        }
+    }
+}
+
+/// Disambiguates a taken short name with what sets the operation apart: the
+/// trailing static path segments (`get` for `.../scripts/{name}/settings`
+/// becomes `get-settings`), else the operationId minus the resource prefix, and
+/// only then a number. The flag says whether it had to fall back to a number.
+fn unique_short_op_name(
+    existing: &HashSet<String>,
+    base: &str,
+    res_name: &str,
+    op_id: &str,
+    path: &str,
+) -> (String, bool) {
+    if !existing.contains(base) {
+        return (base.to_string(), false);
+    }
+
+    let resource_words: HashSet<&str> = res_name.split('-').map(singular).collect();
+    let used: HashSet<&str> = base.split('-').collect();
+    let segments: Vec<String> = path
+        .split('/')
+        .filter(|segment| !segment.is_empty() && !segment.starts_with('{'))
+        .map(|segment| {
+            normalize_name(segment)
+                .split('-')
+                .filter(|word| !word.is_empty() && !FILLER_WORDS.contains(word))
+                .filter(|word| !used.contains(word) && !resource_words.contains(singular(word)))
+                .collect::<Vec<_>>()
+                .join("-")
+        })
+        .filter(|words| !words.is_empty())
+        .collect();
+    let mut candidates: Vec<String> = (1..=segments.len())
+        .map(|count| format!("{base}-{}", segments[segments.len() - count..].join("-")))
+        .collect();
+
+    let op_id = normalize_name(op_id);
+    let rest = op_id
+        .strip_prefix(res_name)
+        .and_then(|rest| rest.strip_prefix('-'))
+        .unwrap_or(&op_id);
+    if !rest.is_empty() {
+        candidates.push(rest.to_string());
+    }
+
+    if let Some(name) = candidates.into_iter().find(|name| !existing.contains(name)) {
+        return (name, false);
+    }
+    let mut idx = 2;
+    loop {
+        let next = format!("{base}-{idx}");
+        if !existing.contains(&next) {
+            return (next, true);
+        }
+        idx += 1;
+    }
+}
+
+const FILLER_WORDS: &[&str] = &["a", "an", "the", "for", "of"];
+const BUILTIN_COMMANDS: &[&str] = &["api", "describe", "help", "list", "permissions", "tree"];
+
+/// Derives a short, verb-first operation name by dropping the resource prefix and
+/// any words that only repeat the resource, e.g. `dns-records-for-a-zone-create-dns-record`
+/// under `dns-records-for-a-zone` becomes `create`.
+fn short_op_name(res_name: &str, op_id: &str, method: &str, path: &str) -> String {
+    let op_id = normalize_name(op_id);
+    let op_id = op_id
+        .strip_prefix(res_name)
+        .and_then(|rest| rest.strip_prefix('-'))
+        .unwrap_or(&op_id);
+    let resource_words: HashSet<&str> = res_name.split('-').map(singular).collect();
+    let words: Vec<&str> = op_id
+        .split('-')
+        .filter(|word| !word.is_empty() && !FILLER_WORDS.contains(word))
+        .filter(|word| !word.chars().all(|ch| ch.is_ascii_digit()))
+        .filter(|word| !resource_words.contains(singular(word)))
+        .collect();
+
+    let Some((first, rest)) = words.split_first() else {
+        return method_verb(method, path).to_string();
+    };
+    let Some(verb) = canonical_verb(first) else {
+        return words.join("-");
+    };
+    if rest.is_empty() {
+        verb.to_string()
+    } else {
+        format!("{verb}-{}", rest.join("-"))
+    }
+}
+
+fn canonical_verb(word: &str) -> Option<&'static str> {
+    match word {
+        "create" | "add" | "new" => Some("create"),
+        "list" => Some("list"),
+        "get" | "read" | "details" | "detail" | "fetch" | "show" | "info" => Some("get"),
+        "update" | "edit" | "modify" => Some("update"),
+        "delete" | "remove" => Some("delete"),
+        _ => None,
+    }
+}
+
+fn method_verb(method: &str, path: &str) -> &'static str {
+    match method {
+        "get" if path.ends_with('}') => "get",
+        "get" => "list",
+        "post" => "create",
+        "put" | "patch" => "update",
+        "delete" => "delete",
+        "head" => "head",
+        _ => "options",
+    }
+}
+
+fn singular(word: &str) -> &str {
+    match word.strip_suffix('s') {
+        Some(stem) if word.len() > 3 && !stem.ends_with('s') => stem,
+        _ => word,
+    }
+}
+
+/// Gives each resource a short alias: the first word of its name goes to the resource
+/// with the most operations among those sharing it (`dns` for `dns-records-for-a-zone`),
+/// the others get their shortest unique leading words.
+fn assign_resource_aliases(resources: &mut [Resource]) {
+    let mut taken: HashSet<String> = resources.iter().map(|res| res.name.clone()).collect();
+    taken.extend(BUILTIN_COMMANDS.iter().map(|name| name.to_string()));
+    taken.extend(
+        resources
+            .iter()
+            .flat_map(|res| &res.ops)
+            .flat_map(|op| op.also_in.iter().map(|xref| xref.resource.clone())),
+    );
+
+    let mut owners: BTreeMap<String, usize> = BTreeMap::new();
+    for (idx, res) in resources.iter().enumerate() {
+        let first = res.name.split('-').next().unwrap_or_default().to_string();
+        let owner = owners.entry(first).or_insert(idx);
+        if res.ops.len() > resources[*owner].ops.len() {
+            *owner = idx;
+        }
+    }
+    for (first, idx) in &owners {
+        if taken.insert(first.clone()) {
+            resources[*idx].aliases.push(first.clone());
+        }
+    }
+
+    for res in resources.iter_mut() {
+        if !res.aliases.is_empty() {
+            continue;
+        }
+        let words: Vec<&str> = res.name.split('-').collect();
+        let stem_len = words
+            .iter()
+            .position(|word| *word == "for")
+            .filter(|idx| *idx > 0)
+            .unwrap_or(words.len());
+        for len in 2..stem_len {
+            let candidate = words[..len].join("-");
+            if taken.insert(candidate.clone()) {
+                res.aliases.push(candidate);
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r#"
+openapi: 3.0.0
+info: {title: t, version: "4"}
+paths:
+  /list:
+    get:
+      responses: {"200": {description: ok}}
+  /zones/{zone_id}/hold:
+    get:
+      parameters:
+        - {name: zone_id, in: path, required: true, schema: {type: string}}
+        - {name: output, in: query, schema: {type: string}}
+      responses: {"200": {description: ok}}
+  /zones:
+    get:
+      operationId: zone-hold-list
+      tags: [Zone Hold]
+      responses: {"200": {description: ok}}
+"#;
+
+    fn lint() -> (CommandTree, LintReport) {
+        lint_spec(&parse_spec(SPEC).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn path_derived_resources_avoid_builtins_and_tags() {
+        let (tree, report) = lint();
+        let names: Vec<&str> = tree.resources.iter().map(|res| res.name.as_str()).collect();
+        assert_eq!(names, ["list-2", "zone-hold", "zone-hold-2"]);
+        assert_eq!(tree.find_op("list-2", "list").map(|op| op.path.as_str()), Some("/list"));
+        assert_eq!(tree.find_op("zone-hold-2", "list").map(|op| op.path.as_str()), Some("/zones/{zone_id}/hold"));
+        let details: Vec<&str> = report
+            .issues
+            .iter()
+            .filter(|issue| issue.kind == LintKind::ResourceNameCollision)
+            .map(|issue| issue.detail.as_str())
+            .collect();
+        assert_eq!(
+            details,
+            [
+                "resource `list` is a tag's or a built-in command's, renamed to `list-2`",
+                "resource `zone-hold` is a tag's or a built-in command's, renamed to `zone-hold-2`",
+            ]
+        );
+    }
+
+    #[test]
+    fn parameters_named_like_cli_flags_are_prefixed() {
+        let (tree, report) = lint();
+        let op = tree.find_op("zone-hold-2", "list").unwrap();
+        let flags: Vec<&str> = op.parameters.iter().map(|param| param.flag.as_str()).collect();
+        assert_eq!(flags, ["param-output", "zone-id"]);
+        let issue = report.issues.iter().find(|issue| issue.kind == LintKind::ReservedFlag).unwrap();
+        assert_eq!(issue.detail, "query `output`: --output is the CLI's own, renamed to --param-output");
+    }
+}
@@ -0,0 +1,297 @@
+use anyhow::{Result, anyhow};
+use serde_json::Value;
+
+/// How API responses are printed (`--output`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Json,
+    Yaml,
+    Table,
+    Csv,
+    Tsv,
+    Ndjson,
+}
+
+/// Widest a table cell gets before it is cut short.
+const MAX_CELL_WIDTH: usize = 60;
+
+impl OutputFormat {
+    pub const NAMES: [&'static str; 6] = ["json", "yaml", "table", "csv", "tsv", "ndjson"];
+
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "json" => Ok(Self::Json),
+            "yaml" => Ok(Self::Yaml),
+            "table" => Ok(Self::Table),
+            "csv" => Ok(Self::Csv),
+            "tsv" => Ok(Self::Tsv),
+            "ndjson" => Ok(Self::Ndjson),
+            other => Err(anyhow!("unknown output format {other}")),
+        }
+    }
+}
+
+/// Renders a response for printing, without a trailing newline. `columns` are the
+/// list fields known from the response schema; tables fall back to the scalar fields
+/// found in the data when they are not known.
+pub fn render(value: &Value, format: OutputFormat, pretty: bool, columns: Option<&[String]>) -> Result<String> {
+    Ok(match format {
+        OutputFormat::Json if pretty => serde_json::to_string_pretty(value)?,
+        OutputFormat::Json => serde_json::to_string(value)?,
+        OutputFormat::Yaml => serde_yaml::to_string(value)?.trim_end().to_string(),
+        OutputFormat::Ndjson => match value {
+            Value::Array(items) => items
+                .iter()
+                .map(serde_json::to_string)
+                .collect::<serde_json::Result<Vec<_>>>()?
+                .join("\n"),
+            other => serde_json::to_string(other)?,
+        },
+        OutputFormat::Table => render_table(value, columns),
+        OutputFormat::Csv => render_delimited(value, ','),
+        OutputFormat::Tsv => render_delimited(value, '\t'),
+    })
+}
+
+/// Keeps only `fields` (dotted paths such as `meta.auto_added`) of an object, or of
+/// each object in a list, in the order given. Selected fields keep their path as
+/// the key and are `null` when missing, so every row has the same columns.
+pub fn select_fields(value: &Value, fields: &[String]) -> Value {
+    let select = |item: &Value| match item {
+        Value::Object(_) => Value::Object(
+            fields
+                .iter()
+                .map(|field| (field.clone(), lookup(item, field).clone()))
+                .collect(),
+        ),
+        other => other.clone(),
+    };
+    match value {
+        Value::Array(items) => Value::Array(items.iter().map(select).collect()),
+        other => select(other),
+    }
+}
+
+fn render_table(value: &Value, columns: Option<&[String]>) -> String {
+    let rows: Vec<Vec<String>> = match value {
+        Value::Array(items) => {
+            let columns: Vec<String> = match columns.filter(|columns| !columns.is_empty()) {
+                Some(columns) => columns.to_vec(),
+                None => scalar_columns(items),
+            };
+            if columns.is_empty() {
+                // Lists of scalars (or of objects without scalar fields).
+                let mut rows = vec![vec!["VALUE".to_string()]];
+                rows.extend(items.iter().map(|item| vec![cell_text(item)]));
+                rows
+            } else {
+                let mut rows = vec![columns.iter().map(|column| column.to_uppercase()).collect()];
+                for item in items {
+                    rows.push(columns.iter().map(|column| cell_text(lookup(item, column))).collect());
+                }
+                rows
+            }
+        }
+        // A single object reads best as one field per line.
+        Value::Object(_) => {
+            let mut rows = vec![vec!["FIELD".to_string(), "VALUE".to_string()]];
+            rows.extend(flatten(value).into_iter().map(|(key, value)| vec![key, cell_text(&value)]));
+            rows
+        }
+        other => return cell_text(other),
+    };
+    if rows.len() == 1 {
+        return String::new();
+    }
+
+    let rows: Vec<Vec<String>> = rows
+        .into_iter()
+        .map(|row| row.into_iter().map(|cell| truncate_cell(&cell)).collect())
+        .collect();
+    let mut widths = vec![0; rows[0].len()];
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let lines: Vec<String> = rows
+        .iter()
+        .map(|row| {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect();
+            cells.join("  ").trim_end().to_string()
+        })
+        .collect();
+    lines.join("\n")
+}
+
+/// CSV (RFC 4180 quoting) or TSV (backslash escapes) with one row per list element
+/// and nested object fields flattened into dotted headers.
+fn render_delimited(value: &Value, delimiter: char) -> String {
+    let items: Vec<&Value> = match value {
+        Value::Array(items) => items.iter().collect(),
+        Value::Null => Vec::new(),
+        other => vec![other],
+    };
+    let rows: Vec<Vec<(String, Value)>> = items.into_iter().map(flatten).collect();
+    let mut headers: Vec<&str> = Vec::new();
+    for (key, _) in rows.iter().flatten() {
+        if !headers.contains(&key.as_str()) {
+            headers.push(key);
+        }
+    }
+    if headers.is_empty() {
+        return String::new();
+    }
+
+    let escape = |text: &str| {
+        if delimiter == '\t' {
+            text.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n").replace('\r', "\\r")
+        } else if text.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", text.replace('"', "\"\""))
+        } else {
+            text.to_string()
+        }
+    };
+    let separator = delimiter.to_string();
+    let mut lines = vec![headers.iter().map(|header| escape(header)).collect::<Vec<_>>().join(&separator)];
+    for row in &rows {
+        let cells: Vec<String> = headers
+            .iter()
+            .map(|header| {
+                row.iter()
+                    .find(|(key, _)| key == header)
+                    .map(|(_, value)| escape(&cell_text(value)))
+                    .unwrap_or_default()
+            })
+            .collect();
+        lines.push(cells.join(&separator));
+    }
+    lines.join("\n")
+}
+
+/// Top-level scalar fields across all rows, in order of first appearance.
+fn scalar_columns(items: &[Value]) -> Vec<String> {
+    let mut columns: Vec<String> = Vec::new();
+    for object in items.iter().filter_map(Value::as_object) {
+        for (key, value) in object {
+            if !value.is_object() && !value.is_array() && !columns.contains(key) {
+                columns.push(key.clone());
+            }
+        }
+    }
+    columns
+}
+
+/// Leaf fields of a value keyed by dotted path; arrays stay whole (as JSON in cells).
+/// A scalar is a single field named `value`.
+fn flatten(value: &Value) -> Vec<(String, Value)> {
+    fn walk(prefix: &str, value: &Value, out: &mut Vec<(String, Value)>) {
+        match value {
+            Value::Object(map) if !map.is_empty() => {
+                for (key, value) in map {
+                    let path = if prefix.is_empty() { key.clone() } else { format!("{prefix}.{key}") };
+                    walk(&path, value, out);
+                }
+            }
+            _ => {
+                let key = if prefix.is_empty() { "value" } else { prefix };
+                out.push((key.to_string(), value.clone()));
+            }
+        }
+    }
+    let mut out = Vec::new();
+    walk("", value, &mut out);
+    out
+}
+
+/// Follows a dotted path (`meta.auto_added`) into nested objects.
+fn lookup<'a>(value: &'a Value, path: &str) -> &'a Value {
+    static NULL: Value = Value::Null;
+    value
+        .get(path)
+        .or_else(|| path.split('.').try_fold(value, |current, key| current.get(key)))
+        .unwrap_or(&NULL)
+}
+
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn truncate_cell(cell: &str) -> String {
+    let cell = cell.replace(['\n', '\r', '\t'], " ");
+    if cell.chars().count() <= MAX_CELL_WIDTH {
+        return cell;
+    }
+    let mut cut: String = cell.chars().take(MAX_CELL_WIDTH - 1).collect();
+    cut.push('…');
+    cut
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn csv(value: &Value) -> String {
+        render(value, OutputFormat::Csv, false, None).unwrap()
+    }
+
+    #[test]
+    fn csv_flattens_nested_objects_into_dotted_headers() {
+        let value = json!([
+            {"id": 1, "meta": {"auto_added": true, "source": {"kind": "api"}}, "tags": ["a", "b"]},
+            {"id": 2, "meta": {}, "extra": null},
+        ]);
+        assert_eq!(
+            csv(&value),
+            "id,meta.auto_added,meta.source.kind,tags,meta,extra\n\
+             1,true,api,\"[\"\"a\"\",\"\"b\"\"]\",,\n\
+             2,,,,{},"
+        );
+    }
+
+    #[test]
+    fn csv_quotes_only_cells_that_need_it() {
+        let value = json!([{"name": "plain", "note": "a, b"}, {"name": "say \"hi\"", "note": "two\nlines"}]);
+        assert_eq!(csv(&value), "name,note\nplain,\"a, b\"\n\"say \"\"hi\"\"\",\"two\nlines\"");
+    }
+
+    #[test]
+    fn tsv_escapes_tabs_newlines_and_backslashes() {
+        let value = json!({"path": "C:\\dir", "text": "a\tb\r\nc"});
+        assert_eq!(
+            render(&value, OutputFormat::Tsv, false, None).unwrap(),
+            "path\ttext\nC:\\\\dir\ta\\tb\\r\\nc"
+        );
+    }
+
+    #[test]
+    fn csv_of_scalars_and_empty_results() {
+        assert_eq!(csv(&json!(["x", 2])), "value\nx\n2");
+        assert_eq!(csv(&json!([])), "");
+        assert_eq!(csv(&Value::Null), "");
+    }
+
+    #[test]
+    fn select_fields_keeps_order_and_fills_missing_with_null() {
+        let value = json!([{"id": 1, "name": "a", "meta": {"auto_added": false}}, {"id": 2}]);
+        let fields = vec!["meta.auto_added".to_string(), "id".to_string()];
+        assert_eq!(
+            select_fields(&value, &fields),
+            json!([{"meta.auto_added": false, "id": 1}, {"meta.auto_added": null, "id": 2}])
+        );
+        assert_eq!(
+            serde_json::to_string(&select_fields(&value[0], &fields)).unwrap(),
+            r#"{"meta.auto_added":false,"id":1}"#
+        );
+        assert_eq!(select_fields(&json!("text"), &fields), json!("text"));
+    }
+}
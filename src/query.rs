@@ -0,0 +1,989 @@
+use anyhow::{Result, anyhow, bail};
+use serde_json::{Map, Number, Value};
+use std::cmp::Ordering;
+
+/// A compiled `--query` expression: a JMESPath subset covering field paths,
+/// indexes and slices, `[*]`/`[]`/`*` projections, `[?cond]` filters, comparisons,
+/// `&&`/`||`/`!`, pipes, multi-select lists and hashes, literals and a set of
+/// built-in functions (`length(@)`, `sort_by(@, &name)`, `contains(name, 'x')`...).
+/// Bare numbers are accepted as literals, so `[?ttl > 300]` and `[?ratio > 0.5]` work
+/// without backticks.
+#[derive(Debug, Clone)]
+pub struct Query {
+    ast: Node,
+}
+
+impl Query {
+    pub fn parse(expr: &str) -> Result<Self> {
+        let tokens = lex(expr)?;
+        let mut parser = Parser { tokens, pos: 0, last: 0 };
+        let ast = parser.expression(0)?;
+        match parser.peek() {
+            Token::Eof => Ok(Self { ast }),
+            token => bail!("unexpected {} at position {}", token.describe(), parser.offset()),
+        }
+    }
+
+    pub fn apply(&self, value: &Value) -> Result<Value> {
+        eval(&self.ast, value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Identifier(String),
+    Literal(Value),
+    Number(i64),
+    Dot,
+    Star,
+    Flatten,
+    Filter,
+    LBracket,
+    RBracket,
+    LBrace,
+    RBrace,
+    LParen,
+    RParen,
+    Comma,
+    Colon,
+    Pipe,
+    Or,
+    And,
+    Not,
+    Current,
+    Expref,
+    Compare(Comparator),
+    Eof,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comparator {
+    Eq,
+    Ne,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+}
+
+impl Token {
+    /// Left binding power, as in the JMESPath reference grammar.
+    fn binding_power(&self) -> u8 {
+        match self {
+            Token::Pipe => 1,
+            Token::Or => 2,
+            Token::And => 3,
+            Token::Compare(_) => 5,
+            Token::Flatten => 9,
+            Token::Star => 20,
+            Token::Filter => 21,
+            Token::Dot => 40,
+            Token::Not => 45,
+            Token::LBrace => 50,
+            Token::LBracket => 55,
+            Token::LParen => 60,
+            _ => 0,
+        }
+    }
+
+    fn symbol(&self) -> &'static str {
+        match self {
+            Token::Dot => ".",
+            Token::Star => "*",
+            Token::Flatten => "[]",
+            Token::Filter => "[?",
+            Token::LBracket => "[",
+            Token::RBracket => "]",
+            Token::LBrace => "{",
+            Token::RBrace => "}",
+            Token::LParen => "(",
+            Token::RParen => ")",
+            Token::Comma => ",",
+            Token::Colon => ":",
+            Token::Pipe => "|",
+            Token::Or => "||",
+            Token::And => "&&",
+            Token::Not => "!",
+            Token::Current => "@",
+            Token::Expref => "&",
+            Token::Compare(Comparator::Eq) => "==",
+            Token::Compare(Comparator::Ne) => "!=",
+            Token::Compare(Comparator::Lt) => "<",
+            Token::Compare(Comparator::Lte) => "<=",
+            Token::Compare(Comparator::Gt) => ">",
+            Token::Compare(Comparator::Gte) => ">=",
+            Token::Identifier(_) | Token::Literal(_) | Token::Number(_) | Token::Eof => "",
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Token::Identifier(name) => format!("`{name}`"),
+            Token::Literal(value) => format!("literal {value}"),
+            Token::Number(n) => format!("number {n}"),
+            Token::Eof => "end of query".to_string(),
+            other => format!("`{}`", other.symbol()),
+        }
+    }
+}
+
+struct Lexed {
+    token: Token,
+    offset: usize,
+}
+
+fn lex(expr: &str) -> Result<Vec<Lexed>> {
+    let chars: Vec<(usize, char)> = expr.char_indices().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let (offset, ch) = chars[i];
+        let next = chars.get(i + 1).map(|(_, ch)| *ch);
+        let mut push = |token: Token, width: usize| {
+            tokens.push(Lexed { token, offset });
+            width
+        };
+        i += match ch {
+            ' ' | '\t' | '\n' | '\r' => 1,
+            '.' => push(Token::Dot, 1),
+            '*' => push(Token::Star, 1),
+            '@' => push(Token::Current, 1),
+            ',' => push(Token::Comma, 1),
+            ':' => push(Token::Colon, 1),
+            '(' => push(Token::LParen, 1),
+            ')' => push(Token::RParen, 1),
+            '{' => push(Token::LBrace, 1),
+            '}' => push(Token::RBrace, 1),
+            ']' => push(Token::RBracket, 1),
+            '[' if next == Some(']') => push(Token::Flatten, 2),
+            '[' if next == Some('?') => push(Token::Filter, 2),
+            '[' => push(Token::LBracket, 1),
+            '|' if next == Some('|') => push(Token::Or, 2),
+            '|' => push(Token::Pipe, 1),
+            '&' if next == Some('&') => push(Token::And, 2),
+            '&' => push(Token::Expref, 1),
+            '!' if next == Some('=') => push(Token::Compare(Comparator::Ne), 2),
+            '!' => push(Token::Not, 1),
+            '=' if next == Some('=') => push(Token::Compare(Comparator::Eq), 2),
+            '<' if next == Some('=') => push(Token::Compare(Comparator::Lte), 2),
+            '<' => push(Token::Compare(Comparator::Lt), 1),
+            '>' if next == Some('=') => push(Token::Compare(Comparator::Gte), 2),
+            '>' => push(Token::Compare(Comparator::Gt), 1),
+            '-' | '0'..='9' => {
+                let digits = |from: usize| chars[from..].iter().take_while(|(_, ch)| ch.is_ascii_digit()).count();
+                let mut len = digits(i + 1) + 1;
+                // A fraction needs a digit after the dot, so `[0].name` is still an index.
+                let is_digit = |at: usize| chars.get(at).is_some_and(|(_, ch)| ch.is_ascii_digit());
+                let fraction = chars.get(i + len).is_some_and(|(_, ch)| *ch == '.') && is_digit(i + len + 1);
+                if fraction {
+                    len += 1 + digits(i + len + 1);
+                }
+                let text: String = chars[i..i + len].iter().map(|(_, ch)| ch).collect();
+                let invalid = || anyhow!("invalid number `{text}` at position {offset}");
+                let token = if fraction {
+                    let number = text.parse::<f64>().ok().and_then(Number::from_f64).ok_or_else(invalid)?;
+                    Token::Literal(Value::Number(number))
+                } else {
+                    Token::Number(text.parse().map_err(|_| invalid())?)
+                };
+                push(token, len)
+            }
+            'a'..='z' | 'A'..='Z' | '_' => {
+                let len = chars[i..]
+                    .iter()
+                    .take_while(|(_, ch)| ch.is_ascii_alphanumeric() || *ch == '_')
+                    .count();
+                let name: String = chars[i..i + len].iter().map(|(_, ch)| ch).collect();
+                push(Token::Identifier(name), len)
+            }
+            '"' | '\'' | '`' => {
+                let (text, len) = delimited(&chars[i..], ch)
+                    .ok_or_else(|| anyhow!("unterminated {ch} at position {offset}"))?;
+                let token = match ch {
+                    '"' => Token::Identifier(
+                        serde_json::from_str(&format!("\"{text}\""))
+                            .map_err(|_| anyhow!("invalid quoted identifier at position {offset}"))?,
+                    ),
+                    '\'' => Token::Literal(Value::String(text.replace("\\'", "'"))),
+                    // Unparseable JSON literals are taken as strings, as older JMESPath did.
+                    _ => {
+                        let text = text.replace("\\`", "`");
+                        Token::Literal(serde_json::from_str(&text).unwrap_or(Value::String(text)))
+                    }
+                };
+                push(token, len)
+            }
+            other => bail!("unexpected `{other}` at position {offset}"),
+        };
+    }
+    tokens.push(Lexed {
+        token: Token::Eof,
+        offset: expr.len(),
+    });
+    Ok(tokens)
+}
+
+/// Text between `quote` and its closing partner (backslash escapes kept), and the
+/// number of chars consumed including both quotes.
+fn delimited(chars: &[(usize, char)], quote: char) -> Option<(String, usize)> {
+    let mut text = String::new();
+    let mut i = 1;
+    while let Some((_, ch)) = chars.get(i) {
+        match ch {
+            '\\' => {
+                text.push('\\');
+                text.push(chars.get(i + 1)?.1);
+                i += 2;
+            }
+            ch if *ch == quote => return Some((text, i + 1)),
+            ch => {
+                text.push(*ch);
+                i += 1;
+            }
+        }
+    }
+    None
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Current,
+    Literal(Value),
+    Field(String),
+    Subexpression(Box<Node>, Box<Node>),
+    Index(i64),
+    Slice(Option<i64>, Option<i64>, Option<i64>),
+    /// Applies the right side to every element of the left side's array.
+    Projection(Box<Node>, Box<Node>),
+    /// Applies the right side to every value of the left side's object.
+    ValueProjection(Box<Node>, Box<Node>),
+    FilterProjection(Box<Node>, Box<Node>, Box<Node>),
+    Flatten(Box<Node>),
+    Pipe(Box<Node>, Box<Node>),
+    Or(Box<Node>, Box<Node>),
+    And(Box<Node>, Box<Node>),
+    Not(Box<Node>),
+    Compare(Comparator, Box<Node>, Box<Node>),
+    MultiSelectList(Vec<Node>),
+    MultiSelectHash(Vec<(String, Node)>),
+    Function(String, Vec<Node>),
+    Expref(Box<Node>),
+}
+
+/// Projections stop at tokens binding looser than this (pipes, `||`, comparisons...).
+const PROJECTION_STOP: u8 = 10;
+
+struct Parser {
+    tokens: Vec<Lexed>,
+    pos: usize,
+    /// Offset of the token `advance` returned last, for errors about that token.
+    last: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos].token
+    }
+
+    fn peek_at(&self, ahead: usize) -> &Token {
+        let idx = (self.pos + ahead).min(self.tokens.len() - 1);
+        &self.tokens[idx].token
+    }
+
+    fn offset(&self) -> usize {
+        self.tokens[self.pos].offset
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.pos].token.clone();
+        self.last = self.tokens[self.pos].offset;
+        if self.pos < self.tokens.len() - 1 {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<()> {
+        if *self.peek() != expected {
+            bail!(
+                "expected {} but found {} at position {}",
+                expected.describe(),
+                self.peek().describe(),
+                self.offset()
+            );
+        }
+        self.advance();
+        Ok(())
+    }
+
+    fn expression(&mut self, binding_power: u8) -> Result<Node> {
+        let token = self.advance();
+        let mut left = self.nud(token)?;
+        while binding_power < self.peek().binding_power() {
+            let token = self.advance();
+            left = self.led(token, left)?;
+        }
+        Ok(left)
+    }
+
+    fn nud(&mut self, token: Token) -> Result<Node> {
+        Ok(match token {
+            Token::Literal(value) => Node::Literal(value),
+            Token::Number(n) => Node::Literal(Value::Number(n.into())),
+            Token::Identifier(name) => Node::Field(name),
+            Token::Current => Node::Current,
+            Token::Star => {
+                let rhs = self.projection_rhs(Token::Star.binding_power())?;
+                Node::ValueProjection(Box::new(Node::Current), Box::new(rhs))
+            }
+            Token::Flatten => {
+                let rhs = self.projection_rhs(Token::Flatten.binding_power())?;
+                Node::Projection(Box::new(Node::Flatten(Box::new(Node::Current))), Box::new(rhs))
+            }
+            Token::Filter => self.filter(Node::Current)?,
+            Token::LBracket => match self.peek() {
+                Token::Number(_) | Token::Colon => self.index_or_slice(Node::Current)?,
+                Token::Star if *self.peek_at(1) == Token::RBracket => {
+                    self.advance();
+                    self.advance();
+                    let rhs = self.projection_rhs(Token::Star.binding_power())?;
+                    Node::Projection(Box::new(Node::Current), Box::new(rhs))
+                }
+                _ => self.multi_select_list()?,
+            },
+            Token::LBrace => self.multi_select_hash()?,
+            Token::LParen => {
+                let inner = self.expression(0)?;
+                self.expect(Token::RParen)?;
+                inner
+            }
+            Token::Not => Node::Not(Box::new(self.expression(Token::Not.binding_power())?)),
+            Token::Expref => Node::Expref(Box::new(self.expression(0)?)),
+            other => bail!("unexpected {} at position {}", other.describe(), self.last),
+        })
+    }
+
+    fn led(&mut self, token: Token, left: Node) -> Result<Node> {
+        let binding_power = token.binding_power();
+        Ok(match token {
+            Token::Dot => {
+                let rhs = self.dot_rhs(binding_power)?;
+                Node::Subexpression(Box::new(left), Box::new(rhs))
+            }
+            Token::Pipe => Node::Pipe(Box::new(left), Box::new(self.expression(binding_power)?)),
+            Token::Or => Node::Or(Box::new(left), Box::new(self.expression(binding_power)?)),
+            Token::And => Node::And(Box::new(left), Box::new(self.expression(binding_power)?)),
+            Token::Compare(op) => Node::Compare(op, Box::new(left), Box::new(self.expression(binding_power)?)),
+            Token::Flatten => {
+                let rhs = self.projection_rhs(binding_power)?;
+                Node::Projection(Box::new(Node::Flatten(Box::new(left))), Box::new(rhs))
+            }
+            Token::Filter => self.filter(left)?,
+            Token::LBracket => match self.peek() {
+                Token::Number(_) | Token::Colon => self.index_or_slice(left)?,
+                Token::Star if *self.peek_at(1) == Token::RBracket => {
+                    self.advance();
+                    self.advance();
+                    let rhs = self.projection_rhs(Token::Star.binding_power())?;
+                    Node::Projection(Box::new(left), Box::new(rhs))
+                }
+                other => bail!("unexpected {} after `[` at position {}", other.describe(), self.offset()),
+            },
+            Token::LParen => {
+                let Node::Field(name) = left else {
+                    bail!("only function names can be called (position {})", self.last);
+                };
+                let mut args = Vec::new();
+                while *self.peek() != Token::RParen {
+                    args.push(self.expression(0)?);
+                    if *self.peek() == Token::Comma {
+                        self.advance();
+                    }
+                }
+                self.advance();
+                check_call(&name, &args)?;
+                Node::Function(name, args)
+            }
+            other => bail!("unexpected {} at position {}", other.describe(), self.last),
+        })
+    }
+
+    fn dot_rhs(&mut self, binding_power: u8) -> Result<Node> {
+        match self.peek() {
+            Token::Identifier(_) | Token::Star | Token::LBrace => self.expression(binding_power),
+            Token::LBracket => {
+                self.advance();
+                self.multi_select_list()
+            }
+            other => bail!("unexpected {} after `.` at position {}", other.describe(), self.offset()),
+        }
+    }
+
+    fn projection_rhs(&mut self, binding_power: u8) -> Result<Node> {
+        match self.peek() {
+            token if token.binding_power() < PROJECTION_STOP => Ok(Node::Current),
+            Token::LBracket | Token::Filter => self.expression(binding_power),
+            Token::Dot => {
+                self.advance();
+                self.dot_rhs(binding_power)
+            }
+            other => bail!("unexpected {} after projection at position {}", other.describe(), self.offset()),
+        }
+    }
+
+    fn filter(&mut self, left: Node) -> Result<Node> {
+        let condition = self.expression(0)?;
+        self.expect(Token::RBracket)?;
+        let rhs = if *self.peek() == Token::Flatten {
+            Node::Current
+        } else {
+            self.projection_rhs(Token::Filter.binding_power())?
+        };
+        Ok(Node::FilterProjection(Box::new(left), Box::new(rhs), Box::new(condition)))
+    }
+
+    /// `[n]`, or a `[start:stop:step]` slice (which projects like `[*]`).
+    fn index_or_slice(&mut self, left: Node) -> Result<Node> {
+        let mut parts: [Option<i64>; 3] = [None; 3];
+        let mut part = 0;
+        loop {
+            match self.advance() {
+                Token::Number(n) => parts[part] = Some(n),
+                Token::Colon if part < 2 => part += 1,
+                Token::RBracket => break,
+                other => bail!("unexpected {} in index at position {}", other.describe(), self.offset()),
+            }
+        }
+        if part == 0 {
+            let index = parts[0].ok_or_else(|| anyhow!("empty index"))?;
+            return Ok(Node::Subexpression(Box::new(left), Box::new(Node::Index(index))));
+        }
+        if parts[2] == Some(0) {
+            bail!("slice step cannot be 0");
+        }
+        let sliced = Node::Subexpression(Box::new(left), Box::new(Node::Slice(parts[0], parts[1], parts[2])));
+        let rhs = self.projection_rhs(Token::Star.binding_power())?;
+        Ok(Node::Projection(Box::new(sliced), Box::new(rhs)))
+    }
+
+    fn multi_select_list(&mut self) -> Result<Node> {
+        let mut items = Vec::new();
+        loop {
+            items.push(self.expression(0)?);
+            match self.advance() {
+                Token::Comma => continue,
+                Token::RBracket => break,
+                other => bail!("expected `,` or `]` but found {}", other.describe()),
+            }
+        }
+        Ok(Node::MultiSelectList(items))
+    }
+
+    fn multi_select_hash(&mut self) -> Result<Node> {
+        let mut pairs = Vec::new();
+        loop {
+            let Token::Identifier(key) = self.advance() else {
+                bail!("expected a key in `{{...}}` at position {}", self.offset());
+            };
+            self.expect(Token::Colon)?;
+            pairs.push((key, self.expression(0)?));
+            match self.advance() {
+                Token::Comma => continue,
+                Token::RBrace => break,
+                other => bail!("expected `,` or `}}` but found {}", other.describe()),
+            }
+        }
+        Ok(Node::MultiSelectHash(pairs))
+    }
+}
+
+fn eval(node: &Node, value: &Value) -> Result<Value> {
+    Ok(match node {
+        Node::Current => value.clone(),
+        Node::Literal(literal) => literal.clone(),
+        Node::Field(name) => value.get(name).cloned().unwrap_or(Value::Null),
+        Node::Subexpression(left, right) => eval(right, &eval(left, value)?)?,
+        Node::Index(index) => match value.as_array() {
+            Some(items) => resolve_index(*index, items.len())
+                .and_then(|idx| items.get(idx).cloned())
+                .unwrap_or(Value::Null),
+            None => Value::Null,
+        },
+        Node::Slice(start, stop, step) => match value.as_array() {
+            Some(items) => Value::Array(slice(items, *start, *stop, step.unwrap_or(1))),
+            None => Value::Null,
+        },
+        Node::Projection(left, right) => match eval(left, value)? {
+            Value::Array(items) => project(&items, right)?,
+            _ => Value::Null,
+        },
+        Node::ValueProjection(left, right) => match eval(left, value)? {
+            Value::Object(object) => project(&object.into_iter().map(|(_, v)| v).collect::<Vec<_>>(), right)?,
+            _ => Value::Null,
+        },
+        Node::FilterProjection(left, right, condition) => match eval(left, value)? {
+            Value::Array(items) => {
+                let mut kept = Vec::new();
+                for item in items {
+                    if truthy(&eval(condition, &item)?) {
+                        kept.push(item);
+                    }
+                }
+                project(&kept, right)?
+            }
+            _ => Value::Null,
+        },
+        Node::Flatten(inner) => match eval(inner, value)? {
+            Value::Array(items) => Value::Array(
+                items
+                    .into_iter()
+                    .flat_map(|item| match item {
+                        Value::Array(nested) => nested,
+                        other => vec![other],
+                    })
+                    .collect(),
+            ),
+            _ => Value::Null,
+        },
+        Node::Pipe(left, right) => eval(right, &eval(left, value)?)?,
+        Node::Or(left, right) => {
+            let left = eval(left, value)?;
+            if truthy(&left) { left } else { eval(right, value)? }
+        }
+        Node::And(left, right) => {
+            let left = eval(left, value)?;
+            if truthy(&left) { eval(right, value)? } else { left }
+        }
+        Node::Not(inner) => Value::Bool(!truthy(&eval(inner, value)?)),
+        Node::Compare(op, left, right) => compare(*op, &eval(left, value)?, &eval(right, value)?),
+        Node::MultiSelectList(items) => {
+            if value.is_null() {
+                return Ok(Value::Null);
+            }
+            Value::Array(items.iter().map(|item| eval(item, value)).collect::<Result<_>>()?)
+        }
+        Node::MultiSelectHash(pairs) => {
+            if value.is_null() {
+                return Ok(Value::Null);
+            }
+            let mut object = Map::new();
+            for (key, item) in pairs {
+                object.insert(key.clone(), eval(item, value)?);
+            }
+            Value::Object(object)
+        }
+        Node::Function(name, args) => call(name, args, value)?,
+        Node::Expref(_) => bail!("`&expression` is only allowed as a function argument"),
+    })
+}
+
+fn project(items: &[Value], right: &Node) -> Result<Value> {
+    let mut out = Vec::new();
+    for item in items {
+        let value = eval(right, item)?;
+        if !value.is_null() {
+            out.push(value);
+        }
+    }
+    Ok(Value::Array(out))
+}
+
+fn resolve_index(index: i64, len: usize) -> Option<usize> {
+    let idx = if index < 0 { len as i64 + index } else { index };
+    (0..len as i64).contains(&idx).then_some(idx as usize)
+}
+
+fn slice(items: &[Value], start: Option<i64>, stop: Option<i64>, step: i64) -> Vec<Value> {
+    let len = items.len() as i64;
+    let clamp = |bound: i64, low: i64, high: i64| {
+        let bound = if bound < 0 { bound + len } else { bound };
+        bound.clamp(low, high)
+    };
+    let mut out = Vec::new();
+    if step > 0 {
+        let mut idx = start.map_or(0, |start| clamp(start, 0, len));
+        let stop = stop.map_or(len, |stop| clamp(stop, 0, len));
+        while idx < stop {
+            out.push(items[idx as usize].clone());
+            idx += step;
+        }
+    } else {
+        let mut idx = start.map_or(len - 1, |start| clamp(start, -1, len - 1));
+        let stop = stop.map_or(-1, |stop| clamp(stop, -1, len - 1));
+        while idx > stop {
+            out.push(items[idx as usize].clone());
+            idx += step;
+        }
+    }
+    out
+}
+
+/// JMESPath truthiness: `false`, `null` and empty strings, arrays and objects are false.
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null | Value::Bool(false) => false,
+        Value::String(text) => !text.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(object) => !object.is_empty(),
+        _ => true,
+    }
+}
+
+fn compare(op: Comparator, left: &Value, right: &Value) -> Value {
+    let ordering = match (left, right) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64().partial_cmp(&b.as_f64()),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        _ => None,
+    };
+    match op {
+        Comparator::Eq => Value::Bool(ordering.map_or(left == right, |ord| ord == Ordering::Equal)),
+        Comparator::Ne => Value::Bool(ordering.map_or(left != right, |ord| ord != Ordering::Equal)),
+        _ => match ordering {
+            Some(ord) => Value::Bool(match op {
+                Comparator::Lt => ord == Ordering::Less,
+                Comparator::Lte => ord != Ordering::Greater,
+                Comparator::Gt => ord == Ordering::Greater,
+                _ => ord != Ordering::Less,
+            }),
+            None => Value::Null,
+        },
+    }
+}
+
+/// Built-in functions: how many arguments each takes (`None`: one or more) and
+/// which one, if any, is an `&expression`.
+const FUNCTIONS: &[(&str, Option<usize>, Option<usize>)] = &[
+    ("abs", Some(1), None),
+    ("avg", Some(1), None),
+    ("ceil", Some(1), None),
+    ("contains", Some(2), None),
+    ("ends_with", Some(2), None),
+    ("floor", Some(1), None),
+    ("join", Some(2), None),
+    ("keys", Some(1), None),
+    ("length", Some(1), None),
+    ("map", Some(2), Some(0)),
+    ("max", Some(1), None),
+    ("max_by", Some(2), Some(1)),
+    ("min", Some(1), None),
+    ("min_by", Some(2), Some(1)),
+    ("not_null", None, None),
+    ("reverse", Some(1), None),
+    ("sort", Some(1), None),
+    ("sort_by", Some(2), Some(1)),
+    ("starts_with", Some(2), None),
+    ("sum", Some(1), None),
+    ("to_number", Some(1), None),
+    ("to_string", Some(1), None),
+    ("type", Some(1), None),
+    ("values", Some(1), None),
+];
+
+/// Rejects unknown functions and wrong argument lists while parsing, so a bad
+/// query fails before the request is sent; `call` relies on these checks.
+fn check_call(name: &str, args: &[Node]) -> Result<()> {
+    let Some((_, arity, expref)) = FUNCTIONS.iter().find(|(known, ..)| *known == name) else {
+        bail!("unknown function {name}()");
+    };
+    match arity {
+        Some(expected) if args.len() != *expected => {
+            bail!("{name}() takes {expected} argument(s), got {}", args.len())
+        }
+        None if args.is_empty() => bail!("{name}() takes at least 1 argument"),
+        _ => {}
+    }
+    if let Some(idx) = expref
+        && !matches!(args[*idx], Node::Expref(_))
+    {
+        bail!("{name}() expects an &expression as argument {}", idx + 1);
+    }
+    Ok(())
+}
+
+fn call(name: &str, args: &[Node], current: &Value) -> Result<Value> {
+    let arg = |idx: usize| eval(&args[idx], current);
+    let expref = |idx: usize| match &args[idx] {
+        Node::Expref(inner) => Ok(inner.as_ref()),
+        _ => Err(anyhow!("{name}() expects an &expression as argument {}", idx + 1)),
+    };
+    let array = |value: Value| match value {
+        Value::Array(items) => Ok(items),
+        other => Err(anyhow!("{name}() expects an array, got {}", type_name(&other))),
+    };
+
+    Ok(match name {
+        "length" => {
+            match arg(0)? {
+                Value::String(text) => json_number(text.chars().count() as f64),
+                Value::Array(items) => json_number(items.len() as f64),
+                Value::Object(object) => json_number(object.len() as f64),
+                other => bail!("length() expects a string, array or object, got {}", type_name(&other)),
+            }
+        }
+        "keys" | "values" => {
+            let Value::Object(object) = arg(0)? else {
+                bail!("{name}() expects an object");
+            };
+            if name == "keys" {
+                Value::Array(object.keys().cloned().map(Value::String).collect())
+            } else {
+                Value::Array(object.values().cloned().collect())
+            }
+        }
+        "type" => {
+            Value::String(type_name(&arg(0)?).to_string())
+        }
+        "not_null" => {
+            for idx in 0..args.len() {
+                let value = arg(idx)?;
+                if !value.is_null() {
+                    return Ok(value);
+                }
+            }
+            Value::Null
+        }
+        "to_string" => {
+            match arg(0)? {
+                Value::String(text) => Value::String(text),
+                other => Value::String(other.to_string()),
+            }
+        }
+        "to_number" => {
+            match arg(0)? {
+                Value::Number(n) => Value::Number(n),
+                Value::String(text) => text.trim().parse::<f64>().map(json_number).unwrap_or(Value::Null),
+                _ => Value::Null,
+            }
+        }
+        "contains" => {
+            let needle = arg(1)?;
+            match arg(0)? {
+                Value::Array(items) => Value::Bool(items.contains(&needle)),
+                Value::String(text) => Value::Bool(needle.as_str().is_some_and(|needle| text.contains(needle))),
+                other => bail!("contains() expects a string or array, got {}", type_name(&other)),
+            }
+        }
+        "starts_with" | "ends_with" => {
+            let (Value::String(text), Value::String(affix)) = (arg(0)?, arg(1)?) else {
+                bail!("{name}() expects two strings");
+            };
+            Value::Bool(if name == "starts_with" { text.starts_with(&affix) } else { text.ends_with(&affix) })
+        }
+        "join" => {
+            let Value::String(glue) = arg(0)? else {
+                bail!("join() expects a string separator");
+            };
+            let parts: Option<Vec<String>> = array(arg(1)?)?.into_iter().map(|item| item.as_str().map(str::to_string)).collect();
+            Value::String(parts.ok_or_else(|| anyhow!("join() expects an array of strings"))?.join(&glue))
+        }
+        "reverse" => {
+            match arg(0)? {
+                Value::String(text) => Value::String(text.chars().rev().collect()),
+                other => Value::Array(array(other)?.into_iter().rev().collect()),
+            }
+        }
+        "sort" => {
+            let mut items = array(arg(0)?)?;
+            items.sort_by(order);
+            Value::Array(items)
+        }
+        "sort_by" => {
+            let key = expref(1)?;
+            let mut keyed = array(arg(0)?)?
+                .into_iter()
+                .map(|item| Ok((eval(key, &item)?, item)))
+                .collect::<Result<Vec<_>>>()?;
+            keyed.sort_by(|a, b| order(&a.0, &b.0));
+            Value::Array(keyed.into_iter().map(|(_, item)| item).collect())
+        }
+        "max" | "min" => {
+            let items = array(arg(0)?)?;
+            let best = items.into_iter().reduce(|a, b| {
+                let keep_a = match order(&a, &b) {
+                    Ordering::Greater => name == "max",
+                    _ => name == "min",
+                };
+                if keep_a { a } else { b }
+            });
+            best.unwrap_or(Value::Null)
+        }
+        "max_by" | "min_by" => {
+            let key = expref(1)?;
+            let mut best: Option<(Value, Value)> = None;
+            for item in array(arg(0)?)? {
+                let item_key = eval(key, &item)?;
+                let better = match &best {
+                    None => true,
+                    Some((best_key, _)) => match order(&item_key, best_key) {
+                        Ordering::Greater => name == "max_by",
+                        Ordering::Less => name == "min_by",
+                        Ordering::Equal => false,
+                    },
+                };
+                if better {
+                    best = Some((item_key, item));
+                }
+            }
+            best.map(|(_, item)| item).unwrap_or(Value::Null)
+        }
+        "sum" | "avg" => {
+            let items = array(arg(0)?)?;
+            let numbers: Option<Vec<f64>> = items.iter().map(Value::as_f64).collect();
+            let numbers = numbers.ok_or_else(|| anyhow!("{name}() expects an array of numbers"))?;
+            let total: f64 = numbers.iter().sum();
+            match name {
+                "sum" => json_number(total),
+                _ if numbers.is_empty() => Value::Null,
+                _ => json_number(total / numbers.len() as f64),
+            }
+        }
+        "abs" | "floor" | "ceil" => {
+            let number = arg(0)?.as_f64().ok_or_else(|| anyhow!("{name}() expects a number"))?;
+            json_number(match name {
+                "abs" => number.abs(),
+                "floor" => number.floor(),
+                _ => number.ceil(),
+            })
+        }
+        "map" => {
+            let key = expref(0)?;
+            let items = array(arg(1)?)?;
+            Value::Array(items.iter().map(|item| eval(key, item)).collect::<Result<_>>()?)
+        }
+        other => bail!("unknown function {other}()"),
+    })
+}
+
+/// Integral results stay integers so `length(@)` prints `3`, not `3.0`.
+fn json_number(number: f64) -> Value {
+    if number.fract() == 0.0 && number.abs() < 9.0e15 {
+        Value::Number((number as i64).into())
+    } else {
+        Number::from_f64(number).map_or(Value::Null, Value::Number)
+    }
+}
+
+/// Sort order for `sort`/`sort_by`/`max`/`min`: numbers, then strings, then the rest.
+fn order(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x.as_f64().partial_cmp(&y.as_f64()).unwrap_or(Ordering::Equal),
+        (Value::String(x), Value::String(y)) => x.cmp(y),
+        (Value::Number(_), _) => Ordering::Less,
+        (_, Value::Number(_)) => Ordering::Greater,
+        (Value::String(_), _) => Ordering::Less,
+        (_, Value::String(_)) => Ordering::Greater,
+        _ => Ordering::Equal,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn records() -> Value {
+        json!([
+            {"name": "a.example.com", "type": "A", "ttl": 300, "proxied": true, "ratio": 0.25, "tags": ["x"]},
+            {"name": "b.example.com", "type": "CNAME", "ttl": 3600, "proxied": false, "ratio": 0.75, "tags": []},
+            {"name": "c.example.com", "type": "A", "ttl": 60, "proxied": true, "ratio": 0.5, "tags": ["x", "y"]},
+        ])
+    }
+
+    fn query(expr: &str, value: &Value) -> Value {
+        Query::parse(expr).unwrap().apply(value).unwrap()
+    }
+
+    fn parse_error(expr: &str) -> String {
+        Query::parse(expr).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn projections() {
+        let data = records();
+        assert_eq!(query("[*].type", &data), json!(["A", "CNAME", "A"]));
+        assert_eq!(query("[].tags[]", &data), json!(["x", "x", "y"]));
+        assert_eq!(query("[0].name", &data), json!("a.example.com"));
+        assert_eq!(query("[*].{n: name, t: ttl} | [1]", &data), json!({"n": "b.example.com", "t": 3600}));
+        assert_eq!(query("[*].[type, proxied][0]", &data), json!(["A", true]));
+        assert_eq!(query("*", &json!({"a": 1, "b": 2})), json!([1, 2]));
+        assert_eq!(query("[*].missing", &data), json!([]));
+    }
+
+    #[test]
+    fn filters() {
+        let data = records();
+        assert_eq!(query("[?proxied].name", &data), json!(["a.example.com", "c.example.com"]));
+        assert_eq!(query("[?ttl > 300].name", &data), json!(["b.example.com"]));
+        assert_eq!(query("[?type == 'A' && ttl < `100`].name", &data), json!(["c.example.com"]));
+        assert_eq!(query("[?!proxied || ttl == `60`] | length(@)", &data), json!(2));
+        assert_eq!(query("[?contains(tags, 'y')].name", &data), json!(["c.example.com"]));
+    }
+
+    #[test]
+    fn decimal_literals() {
+        let data = records();
+        assert_eq!(query("[?ratio > 0.5].name", &data), json!(["b.example.com"]));
+        assert_eq!(query("[?ratio >= `0.5`].name", &data), json!(["b.example.com", "c.example.com"]));
+        assert_eq!(query("[?ratio < -0.5]", &data), json!([]));
+        assert_eq!(query("[?ratio == 0.25].ttl", &data), json!([300]));
+    }
+
+    #[test]
+    fn slices() {
+        let data = json!([0, 1, 2, 3, 4, 5]);
+        assert_eq!(query("[1:3]", &data), json!([1, 2]));
+        assert_eq!(query("[-2:]", &data), json!([4, 5]));
+        assert_eq!(query("[::2]", &data), json!([0, 2, 4]));
+        assert_eq!(query("[::-1]", &data), json!([5, 4, 3, 2, 1, 0]));
+        assert_eq!(query("[-1]", &data), json!(5));
+        assert_eq!(query("[10]", &data), Value::Null);
+    }
+
+    #[test]
+    fn functions() {
+        let data = records();
+        assert_eq!(query("length(@)", &data), json!(3));
+        assert_eq!(query("sort_by(@, &ttl)[*].ttl", &data), json!([60, 300, 3600]));
+        assert_eq!(query("max_by(@, &ratio).name", &data), json!("b.example.com"));
+        assert_eq!(query("sum([*].ttl)", &data), json!(3960));
+        assert_eq!(query("avg([*].ratio)", &data), json!(0.5));
+        assert_eq!(query("join(', ', [*].type)", &data), json!("A, CNAME, A"));
+        assert_eq!(query("keys([0]) | sort(@) | [0]", &data), json!("name"));
+        assert_eq!(query("map(&starts_with(name, 'b'), @)", &data), json!([false, true, false]));
+        assert_eq!(query("to_number('42')", &data), json!(42));
+        assert_eq!(query("type([0].ratio)", &data), json!("number"));
+    }
+
+    #[test]
+    fn parse_errors_point_at_the_problem() {
+        assert_eq!(parse_error("[?ttl > ]"), "unexpected `]` at position 8");
+        assert_eq!(parse_error("name extra"), "unexpected `extra` at position 5");
+        assert_eq!(parse_error("'open"), "unterminated ' at position 0");
+        assert_eq!(parse_error("a # b"), "unexpected `#` at position 2");
+        assert_eq!(parse_error("[0:1:0]"), "slice step cannot be 0");
+        assert_eq!(parse_error("foo."), "unexpected end of query after `.` at position 4");
+        assert_eq!(parse_error("[?nope(@)]"), "unknown function nope()");
+        assert_eq!(parse_error("length(@, @)"), "length() takes 1 argument(s), got 2");
+        assert_eq!(parse_error("not_null()"), "not_null() takes at least 1 argument");
+        assert_eq!(parse_error("sort_by(@, ttl)"), "sort_by() expects an &expression as argument 2");
+    }
+
+    #[test]
+    fn evaluation_errors_name_the_function() {
+        let data = records();
+        let error = |expr: &str| Query::parse(expr).unwrap().apply(&data).unwrap_err().to_string();
+        assert_eq!(error("length(`1`)"), "length() expects a string, array or object, got number");
+        assert_eq!(error("sum([*].name)"), "sum() expects an array of numbers");
+    }
+}
@@ -0,0 +1,96 @@
+use anyhow::{Context, Result};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use crate::command_tree::{ChunkedTree, CommandTree};
+use crate::openapi;
+
+/// Loads a command tree JSON file (as written by `gen_command_tree --out`).
+pub fn load_tree_file(path: &str) -> Result<ChunkedTree<'static>> {
+    let raw = fs::read_to_string(path).with_context(|| format!("read tree {path}"))?;
+    cached("tree", &raw, || {
+        serde_json::from_str(&raw).with_context(|| format!("parse tree {path}"))
+    })
+}
+
+/// Builds the command tree from an OpenAPI spec, as `gen_command_tree` would.
+pub fn load_openapi(path: &str) -> Result<ChunkedTree<'static>> {
+    let raw = fs::read_to_string(path).with_context(|| format!("read openapi {path}"))?;
+    cached("openapi", &raw, || {
+        let doc = openapi::parse_spec(&raw)?;
+        openapi::build_command_tree(&doc).with_context(|| format!("build command tree from {path}"))
+    })
+}
+
+/// Version of what is cached: bump it whenever the generator's output or the chunk
+/// encoding changes, so trees cached by an older build are not reused even when
+/// the crate version stays the same.
+const CACHE_FORMAT: &str = "3";
+
+/// Returns the chunked form of a tree derived from `raw`, reusing the copy cached
+/// under a hash of `raw`, this CLI's version and `CACHE_FORMAT`, or building and
+/// caching it. A cached copy that does not hold together is rebuilt. Caching is
+/// best effort: without a cache directory, or when it cannot be written, the tree
+/// is simply built every time.
+fn cached(kind: &str, raw: &str, build: impl FnOnce() -> Result<CommandTree>) -> Result<ChunkedTree<'static>> {
+    let hash = fnv1a(&[env!("CARGO_PKG_VERSION").as_bytes(), CACHE_FORMAT.as_bytes(), raw.as_bytes()]);
+    let dir = cache_dir().map(|dir| dir.join(format!("{kind}-{hash:016x}")));
+
+    if let Some(dir) = &dir
+        && dir.exists()
+    {
+        match read_cached(dir) {
+            Some(tree) => return Ok(tree),
+            None => {
+                let _ = fs::remove_dir_all(dir);
+            }
+        }
+    }
+
+    let (index, chunks) = ChunkedTree::split(&build()?).context("encode command tree")?;
+    if let Some(dir) = &dir {
+        write_cached(dir, &index, &chunks);
+    }
+    ChunkedTree::parse(&index, chunks).context("decode command tree")
+}
+
+fn read_cached(dir: &Path) -> Option<ChunkedTree<'static>> {
+    let index = fs::read_to_string(dir.join("index.json")).ok()?;
+    let chunks = fs::read_to_string(dir.join("chunks.json")).ok()?;
+    ChunkedTree::parse(&index, chunks).ok().filter(ChunkedTree::spans_valid)
+}
+
+/// Writes both files into a scratch directory and renames it into place, so other
+/// processes see either no cache entry or a complete one.
+fn write_cached(dir: &Path, index: &str, chunks: &str) {
+    let scratch = dir.with_extension(format!("tmp-{}", std::process::id()));
+    let written = fs::create_dir_all(&scratch)
+        .and_then(|_| fs::write(scratch.join("chunks.json"), chunks))
+        .and_then(|_| fs::write(scratch.join("index.json"), index))
+        .and_then(|_| fs::rename(&scratch, dir));
+    // Another process may have renamed its copy into place first.
+    if written.is_err() {
+        let _ = fs::remove_dir_all(&scratch);
+    }
+}
+
+fn cache_dir() -> Option<PathBuf> {
+    let base = env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+    Some(base.join("cloudflare-cli"))
+}
+
+/// 64-bit FNV-1a; stable across builds, unlike `DefaultHasher`. Parts are
+/// separated by a zero byte so that moving bytes between them changes the hash.
+fn fnv1a(parts: &[&[u8]]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in parts.iter().flat_map(|part| part.iter().chain([&0])) {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
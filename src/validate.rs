@@ -0,0 +1,316 @@
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// A place where a JSON document disagrees with its schema.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Violation {
+    /// JSON pointer to the offending value (`""` is the document root).
+    pub pointer: String,
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pointer = if self.pointer.is_empty() { "(root)" } else { &self.pointer };
+        write!(f, "{pointer}: {}", self.message)
+    }
+}
+
+/// Checks a value against a resolved request schema: types, `nullable`, enums,
+/// required properties, `additionalProperties`, numeric bounds, array items and
+/// `anyOf`/`oneOf`/`allOf`. Keywords it does not know are ignored, so a schema the
+/// generator could not fully describe never rejects a body. Every violation is
+/// returned, not just the first.
+pub fn validate(schema: &Value, value: &Value) -> Vec<Violation> {
+    let mut out = Vec::new();
+    check(schema, value, "", &mut out);
+    out.sort();
+    out.dedup();
+    out
+}
+
+fn check(schema: &Value, value: &Value, pointer: &str, out: &mut Vec<Violation>) {
+    let Some(schema) = schema.as_object() else {
+        return;
+    };
+    let mut violation = |message: String| {
+        out.push(Violation {
+            pointer: pointer.to_string(),
+            message,
+        })
+    };
+
+    if value.is_null() {
+        let nullable = schema.get("nullable").and_then(Value::as_bool).unwrap_or(false);
+        let allows_null = |key: &str| {
+            schema
+                .get(key)
+                .and_then(Value::as_array)
+                .is_some_and(|variants| variants.iter().any(|variant| variant.get("type").and_then(Value::as_str) == Some("null")))
+        };
+        if nullable || allows_null("anyOf") || allows_null("oneOf") || !schema.contains_key("type") {
+            return;
+        }
+    }
+
+    if let Some(expected) = schema.get("type").and_then(Value::as_str)
+        && !type_matches(expected, value)
+    {
+        violation(format!("expected {expected}, got {}", type_name(value)));
+        return;
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array)
+        && !allowed.is_empty()
+        && !allowed.iter().any(|candidate| same_value(candidate, value))
+    {
+        let choices: Vec<String> = allowed.iter().map(Value::to_string).collect();
+        violation(format!("{value} is not one of {}", choices.join(", ")));
+    }
+
+    if let Some(number) = value.as_f64() {
+        if let Some(min) = schema.get("minimum").and_then(Value::as_f64).filter(|min| number < *min) {
+            violation(format!("{value} is less than the minimum {min}"));
+        }
+        if let Some(max) = schema.get("maximum").and_then(Value::as_f64).filter(|max| number > *max) {
+            violation(format!("{value} is greater than the maximum {max}"));
+        }
+    }
+
+    // The generator folds `anyOf`/`oneOf` variants into their parent for flags and
+    // templates; what a variant defines is judged by the variants alone, so a body
+    // that matches the second variant is not held to the first one's types.
+    let variants: Vec<&Map<String, Value>> = ["anyOf", "oneOf"]
+        .iter()
+        .filter_map(|key| schema.get(*key).and_then(Value::as_array))
+        .flatten()
+        .filter_map(Value::as_object)
+        .collect();
+    match value {
+        Value::Object(object) => check_object(schema, &variants, object, pointer, out),
+        Value::Array(items) => {
+            let item_schema = schema.get("items").filter(|_| !variants.iter().any(|v| v.contains_key("items")));
+            if let Some(item_schema) = item_schema {
+                for (idx, item) in items.iter().enumerate() {
+                    check(item_schema, item, &format!("{pointer}/{idx}"), out);
+                }
+            }
+        }
+        _ => {}
+    }
+
+    if let Some(parts) = schema.get("allOf").and_then(Value::as_array) {
+        for part in parts {
+            check(part, value, pointer, out);
+        }
+    }
+    for key in ["anyOf", "oneOf"] {
+        if let Some(variants) = schema.get(key).and_then(Value::as_array) {
+            check_variants(variants, value, pointer, out);
+        }
+    }
+}
+
+fn check_object(
+    schema: &Map<String, Value>,
+    variants: &[&Map<String, Value>],
+    object: &Map<String, Value>,
+    pointer: &str,
+    out: &mut Vec<Violation>,
+) {
+    for name in schema.get("required").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str) {
+        if !object.contains_key(name) {
+            out.push(Violation {
+                pointer: pointer.to_string(),
+                message: format!("missing required property `{name}`"),
+            });
+        }
+    }
+
+    let properties = schema.get("properties").and_then(Value::as_object);
+    let in_variant = |name: &str| {
+        variants.iter().any(|variant| {
+            variant
+                .get("properties")
+                .and_then(Value::as_object)
+                .is_some_and(|own| own.contains_key(name))
+        })
+    };
+    for (name, item) in object {
+        if in_variant(name) {
+            continue;
+        }
+        let child = format!("{pointer}/{}", escape_pointer(name));
+        match (properties.and_then(|properties| properties.get(name)), schema.get("additionalProperties")) {
+            (Some(property), _) => check(property, item, &child, out),
+            // With variants, extra properties are judged by the variant that matches.
+            (None, Some(Value::Bool(false))) if variants.is_empty() => out.push(Violation {
+                pointer: child,
+                message: "unknown property".to_string(),
+            }),
+            (None, Some(extra @ Value::Object(_))) => check(extra, item, &child, out),
+            _ => {}
+        }
+    }
+}
+
+/// A value matching any variant is fine; otherwise the violations of the closest
+/// variant are reported.
+fn check_variants(variants: &[Value], value: &Value, pointer: &str, out: &mut Vec<Violation>) {
+    let mut closest: Option<Vec<Violation>> = None;
+    for variant in variants {
+        let mut found = Vec::new();
+        check(variant, value, pointer, &mut found);
+        if found.is_empty() {
+            return;
+        }
+        if closest.as_ref().is_none_or(|best| found.len() < best.len()) {
+            closest = Some(found);
+        }
+    }
+    out.extend(closest.unwrap_or_default());
+}
+
+fn type_matches(expected: &str, value: &Value) -> bool {
+    match expected {
+        "string" => value.is_string(),
+        "integer" => value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0),
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Enum comparison that treats `1` and `1.0` alike.
+fn same_value(a: &Value, b: &Value) -> bool {
+    match (a.as_f64(), b.as_f64()) {
+        (Some(x), Some(y)) => x == y,
+        _ => a == b,
+    }
+}
+
+fn escape_pointer(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::openapi::{parse_spec, resolve_schema};
+    use serde_json::json;
+
+    fn messages(schema: &Value, value: &Value) -> Vec<String> {
+        validate(schema, value).iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn pointers_lead_to_the_offending_value() {
+        let schema = json!({
+            "type": "object",
+            "required": ["name"],
+            "additionalProperties": false,
+            "properties": {
+                "name": {"type": "string"},
+                "ttl": {"type": "integer", "minimum": 60},
+                "data": {
+                    "type": "object",
+                    "properties": {"a/b": {"type": "boolean"}, "tags": {"type": "array", "items": {"type": "string"}}},
+                },
+            },
+        });
+        let body = json!({"ttl": 30, "extra": 1, "data": {"a/b": "yes", "tags": ["x", 2]}});
+        assert_eq!(
+            messages(&schema, &body),
+            vec![
+                "(root): missing required property `name`",
+                "/data/a~1b: expected boolean, got string",
+                "/data/tags/1: expected string, got number",
+                "/extra: unknown property",
+                "/ttl: 30 is less than the minimum 60",
+            ]
+        );
+    }
+
+    #[test]
+    fn wrong_type_stops_at_the_value() {
+        let schema = json!({"type": "object", "properties": {"proxied": {"type": "boolean", "enum": [true]}}});
+        assert_eq!(messages(&schema, &json!({"proxied": "no"})), vec!["/proxied: expected boolean, got string"]);
+        assert_eq!(messages(&schema, &json!([])), vec!["(root): expected object, got array"]);
+    }
+
+    #[test]
+    fn null_is_allowed_only_when_nullable() {
+        let schema = json!({"type": "object", "properties": {
+            "comment": {"type": "string", "nullable": true},
+            "name": {"type": "string"},
+        }});
+        assert_eq!(
+            messages(&schema, &json!({"comment": null, "name": null})),
+            vec!["/name: expected string, got null"]
+        );
+    }
+
+    #[test]
+    fn body_matching_a_later_variant_passes() {
+        let spec = r#"
+openapi: 3.0.0
+info: {title: t, version: "1"}
+paths: {}
+components:
+  schemas:
+    record:
+      oneOf:
+        - type: object
+          required: [kind, value]
+          additionalProperties: false
+          properties:
+            kind: {type: string, enum: [text]}
+            value: {type: string}
+        - type: object
+          required: [kind, value]
+          additionalProperties: false
+          properties:
+            kind: {type: string, enum: [weight]}
+            value: {type: integer}
+"#;
+        let doc = parse_spec(spec).unwrap();
+        let schema = resolve_schema(&doc, &doc["components"]["schemas"]["record"]);
+
+        assert!(validate(&schema, &json!({"kind": "text", "value": "hello"})).is_empty());
+        assert!(validate(&schema, &json!({"kind": "weight", "value": 10})).is_empty());
+        assert!(!validate(&schema, &json!({"kind": "weight", "value": "heavy"})).is_empty());
+        assert_eq!(
+            messages(&schema, &json!({"kind": "text", "value": "x", "extra": true})),
+            vec!["/extra: unknown property"]
+        );
+        assert_eq!(messages(&schema, &json!({"kind": "text"})), vec!["(root): missing required property `value`"]);
+    }
+
+    #[test]
+    fn properties_outside_the_variants_are_still_checked() {
+        let schema = json!({
+            "type": "object",
+            "properties": {"zone": {"type": "string"}, "value": {"type": "string"}},
+            "anyOf": [
+                {"properties": {"value": {"type": "string"}}},
+                {"properties": {"value": {"type": "array", "items": {"type": "string"}}}},
+            ],
+        });
+        assert!(validate(&schema, &json!({"zone": "z", "value": ["a", "b"]})).is_empty());
+        assert_eq!(messages(&schema, &json!({"zone": 1, "value": "a"})), vec!["/zone: expected string, got number"]);
+    }
+}